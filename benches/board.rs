@@ -0,0 +1,175 @@
+use baduk_rs::{Color, GameState, GameTree, Position};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::collections::HashSet;
+use std::convert::TryFrom;
+
+/// The board as it was stored before bit boards, one `Option<Color>` per point, kept here to
+/// compare against
+#[derive(Clone)]
+struct VecBoard {
+    board: Vec<Option<Color>>,
+    width: u32,
+    height: u32,
+}
+
+impl VecBoard {
+    fn new(width: u32, height: u32) -> VecBoard {
+        VecBoard {
+            board: vec![None; (width * height) as usize],
+            width,
+            height,
+        }
+    }
+
+    fn get_stone(&self, pos: Position) -> Option<&Color> {
+        if pos.x() < 1 || pos.x() > self.width || pos.y() < 1 || pos.y() > self.height {
+            return None;
+        }
+        self.board[self.index(pos)].as_ref()
+    }
+
+    fn place_stone(&self, pos: Position, color: Color) -> Option<VecBoard> {
+        let index = self.index(pos);
+        let mut state = self.clone();
+        match state.board[index] {
+            None => state.board[index] = Some(color),
+            Some(_) => return None,
+        }
+        Some(state)
+    }
+
+    fn count_liberties(&self, pos: Position) -> Option<u32> {
+        Some(self.get_chain(pos)?.iter().fold(0, |acc, pos| {
+            acc + self.get_neighbours(*pos).iter().filter(|pos| self.get_stone(**pos).is_none()).count() as u32
+        }))
+    }
+
+    fn remove_dead_stones(&mut self, color: Color) -> Vec<Position> {
+        let mut dead_stones = vec![];
+        for x in 1..=self.width {
+            for y in 1..=self.height {
+                let pos = Position::from((x, y));
+                if self.get_stone(pos) != Some(&color) {
+                    continue;
+                }
+                if self.count_liberties(pos) == Some(0) {
+                    for pos in self.get_chain(pos).unwrap_or_default() {
+                        let index = self.index(pos);
+                        self.board[index] = None;
+                        dead_stones.push(pos);
+                    }
+                }
+            }
+        }
+        dead_stones
+    }
+
+    fn is_valid(&self) -> bool {
+        (1..=self.width).all(|x| (1..=self.height).all(|y| self.count_liberties((x, y).into()) != Some(0)))
+    }
+
+    fn get_chain(&self, pos: Position) -> Option<Vec<Position>> {
+        let stone = self.get_stone(pos)?;
+        let mut tried: HashSet<Position> = HashSet::default();
+        let mut chain = vec![pos];
+        let mut pool = vec![pos];
+        tried.insert(pos);
+        while let Some(pos) = pool.pop() {
+            for n in self.get_neighbours(pos) {
+                if !tried.contains(&n) && self.get_stone(n) == Some(stone) {
+                    chain.push(n);
+                    pool.push(n);
+                }
+                tried.insert(n);
+            }
+        }
+        Some(chain)
+    }
+
+    fn get_neighbours(&self, pos: Position) -> Vec<Position> {
+        let mut neighbours = vec![];
+        if pos.x() > 1 {
+            neighbours.push((pos.x() - 1, pos.y()).into());
+        }
+        if pos.x() < self.width {
+            neighbours.push((pos.x() + 1, pos.y()).into());
+        }
+        if pos.y() > 1 {
+            neighbours.push((pos.x(), pos.y() - 1).into());
+        }
+        if pos.y() < self.height {
+            neighbours.push((pos.x(), pos.y() + 1).into());
+        }
+        neighbours
+    }
+
+    fn index(&self, pos: Position) -> usize {
+        ((pos.x() - 1) + (pos.y() - 1) * self.width) as usize
+    }
+}
+
+/// Plays a deterministic pseudo random game, skipping moves that are not legal
+fn random_game(moves: usize) -> GameTree {
+    let mut game = GameTree::from(GameState::new(19, 19));
+    let mut seed: u64 = 42;
+    let mut color = Color::Black;
+    let mut played = 0;
+    while played < moves {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        let x = (seed >> 33) as u32 % 19 + 1;
+        let y = (seed >> 45) as u32 % 19 + 1;
+        if game.play_move((x, y), color).is_ok() {
+            color = !color;
+            played += 1;
+        }
+    }
+    game
+}
+
+fn played_moves(game: &GameTree) -> Vec<(Position, Color)> {
+    game.numbered_moves(game.current)
+        .into_iter()
+        .filter_map(|(_, color, pos)| pos.map(|pos| (pos, color)))
+        .collect()
+}
+
+fn playout(c: &mut Criterion) {
+    let moves = played_moves(&random_game(200));
+    let mut group = c.benchmark_group("playout");
+    group.bench_function("bitboard", |b| {
+        b.iter(|| {
+            let mut state = GameState::new(19, 19);
+            for (pos, color) in &moves {
+                let mut next = state.place_stone(*pos, *color).unwrap();
+                next.remove_dead_stones(!*color);
+                assert!(next.is_valid());
+                state = next;
+            }
+            black_box(state)
+        })
+    });
+    group.bench_function("vec", |b| {
+        b.iter(|| {
+            let mut state = VecBoard::new(19, 19);
+            for (pos, color) in &moves {
+                let mut next = state.place_stone(*pos, *color).unwrap();
+                next.remove_dead_stones(!*color);
+                assert!(next.is_valid());
+                state = next;
+            }
+            black_box(state.board.len())
+        })
+    });
+    group.finish();
+}
+
+fn sgf_replay(c: &mut Criterion) {
+    let sgf = random_game(200).to_sgf();
+    c.bench_function("sgf_replay", |b| b.iter(|| GameTree::try_from(black_box(sgf.as_str())).unwrap()));
+    c.bench_function("sgf_replay_checkpoints", |b| {
+        b.iter(|| baduk_rs::sgf::parse_with_checkpoints(black_box(sgf.as_str()), 16).unwrap())
+    });
+}
+
+criterion_group!(benches, playout, sgf_replay);
+criterion_main!(benches);
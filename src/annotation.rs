@@ -0,0 +1,210 @@
+use crate::{GameTree, BadukError, BadukErrorKind, SgfToken};
+use crate::game::GameTreeIndex;
+
+/// Strength of an annotation, the SGF `Double` value type
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Emphasis {
+    Normal,
+    Emphasized,
+}
+
+impl Emphasis {
+    fn from_value(value: &str) -> Option<Emphasis> {
+        match value {
+            "1" => Some(Emphasis::Normal),
+            "2" => Some(Emphasis::Emphasized),
+            _ => None,
+        }
+    }
+
+    fn to_value(self) -> String {
+        match self {
+            Emphasis::Normal => "1".to_string(),
+            Emphasis::Emphasized => "2".to_string(),
+        }
+    }
+}
+
+/// Annotation of the move played in a node. A node can have at most one.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MoveAnnotation {
+    /// `BM`
+    BadMove(Emphasis),
+    /// `DO`
+    Doubtful,
+    /// `IT`
+    Interesting,
+    /// `TE`
+    Tesuji(Emphasis),
+}
+
+impl MoveAnnotation {
+    const IDENTIFIERS: [&'static str; 4] = ["BM", "DO", "IT", "TE"];
+
+    fn from_token(token: &SgfToken) -> Option<MoveAnnotation> {
+        match token {
+            SgfToken::Unknown((ident, value)) => match ident.as_str() {
+                "BM" => Emphasis::from_value(value).map(MoveAnnotation::BadMove),
+                "DO" => Some(MoveAnnotation::Doubtful),
+                "IT" => Some(MoveAnnotation::Interesting),
+                "TE" => Emphasis::from_value(value).map(MoveAnnotation::Tesuji),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn to_token(self) -> SgfToken {
+        let (ident, value) = match self {
+            MoveAnnotation::BadMove(emphasis) => ("BM", emphasis.to_value()),
+            MoveAnnotation::Doubtful => ("DO", String::new()),
+            MoveAnnotation::Interesting => ("IT", String::new()),
+            MoveAnnotation::Tesuji(emphasis) => ("TE", emphasis.to_value()),
+        };
+        SgfToken::Unknown((ident.to_string(), value))
+    }
+}
+
+/// Evaluation of the position in a node. A node can have at most one.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PositionAnnotation {
+    /// `GB`
+    GoodForBlack(Emphasis),
+    /// `GW`
+    GoodForWhite(Emphasis),
+    /// `DM`
+    Even(Emphasis),
+    /// `UC`
+    Unclear(Emphasis),
+}
+
+impl PositionAnnotation {
+    const IDENTIFIERS: [&'static str; 4] = ["GB", "GW", "DM", "UC"];
+
+    fn from_token(token: &SgfToken) -> Option<PositionAnnotation> {
+        match token {
+            SgfToken::Unknown((ident, value)) => {
+                let emphasis = Emphasis::from_value(value)?;
+                match ident.as_str() {
+                    "GB" => Some(PositionAnnotation::GoodForBlack(emphasis)),
+                    "GW" => Some(PositionAnnotation::GoodForWhite(emphasis)),
+                    "DM" => Some(PositionAnnotation::Even(emphasis)),
+                    "UC" => Some(PositionAnnotation::Unclear(emphasis)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn to_token(self) -> SgfToken {
+        let (ident, emphasis) = match self {
+            PositionAnnotation::GoodForBlack(emphasis) => ("GB", emphasis),
+            PositionAnnotation::GoodForWhite(emphasis) => ("GW", emphasis),
+            PositionAnnotation::Even(emphasis) => ("DM", emphasis),
+            PositionAnnotation::Unclear(emphasis) => ("UC", emphasis),
+        };
+        SgfToken::Unknown((ident.to_string(), emphasis.to_value()))
+    }
+}
+
+impl GameTree {
+    pub fn move_annotation(&self, node: GameTreeIndex) -> Option<MoveAnnotation> {
+        self.nodes[node].tokens.iter().find_map(MoveAnnotation::from_token)
+    }
+
+    /// Sets the annotation for the move in a node, replacing any existing one. Fails if the node
+    /// does not contain a move.
+    pub fn set_move_annotation(&mut self, node: GameTreeIndex, annotation: Option<MoveAnnotation>) -> Result<GameTreeIndex, BadukError> {
+        let has_move = self.nodes[node]
+            .tokens
+            .iter()
+            .any(|token| matches!(token, SgfToken::Move { .. }));
+        if annotation.is_some() && !has_move {
+            return Err(BadukErrorKind::MissingMove.into());
+        }
+        self.replace_token(
+            node,
+            |token| has_identifier(token, &MoveAnnotation::IDENTIFIERS),
+            annotation.map(MoveAnnotation::to_token),
+        );
+        Ok(node)
+    }
+
+    pub fn position_annotation(&self, node: GameTreeIndex) -> Option<PositionAnnotation> {
+        self.nodes[node].tokens.iter().find_map(PositionAnnotation::from_token)
+    }
+
+    /// Sets the evaluation of the position in a node, replacing any existing one
+    pub fn set_position_annotation(&mut self, node: GameTreeIndex, annotation: Option<PositionAnnotation>) -> GameTreeIndex {
+        self.replace_token(
+            node,
+            |token| has_identifier(token, &PositionAnnotation::IDENTIFIERS),
+            annotation.map(PositionAnnotation::to_token),
+        );
+        node
+    }
+
+    /// Gets the hotspot marking of a node, `HO`, used for nodes containing a game-deciding move
+    pub fn hotspot(&self, node: GameTreeIndex) -> Option<Emphasis> {
+        self.find_unknown_value(node, "HO").and_then(Emphasis::from_value)
+    }
+
+    pub fn set_hotspot(&mut self, node: GameTreeIndex, hotspot: Option<Emphasis>) -> GameTreeIndex {
+        self.replace_token(
+            node,
+            |token| has_identifier(token, &["HO"]),
+            hotspot.map(|emphasis| SgfToken::Unknown(("HO".to_string(), emphasis.to_value()))),
+        );
+        node
+    }
+
+    /// Gets the estimated score of a node, `V`. Positive values are good for Black.
+    pub fn position_value(&self, node: GameTreeIndex) -> Option<f32> {
+        self.find_unknown_value(node, "V").and_then(|value| value.trim().parse().ok())
+    }
+
+    pub fn set_position_value(&mut self, node: GameTreeIndex, value: Option<f32>) -> GameTreeIndex {
+        self.replace_token(
+            node,
+            |token| has_identifier(token, &["V"]),
+            value.map(|value| SgfToken::Unknown(("V".to_string(), value.to_string()))),
+        );
+        node
+    }
+
+    /// Gets all nodes with a move annotated as a bad move
+    pub fn bad_moves(&self) -> Vec<GameTreeIndex> {
+        (0..self.nodes.len())
+            .filter(|node| matches!(self.move_annotation(*node), Some(MoveAnnotation::BadMove(_))))
+            .collect()
+    }
+
+    /// Gets all nodes marked as hotspots
+    pub fn hotspots(&self) -> Vec<GameTreeIndex> {
+        (0..self.nodes.len())
+            .filter(|node| self.hotspot(*node).is_some())
+            .collect()
+    }
+
+    /// Gets all nodes flagged for review, either as a bad move or as a hotspot
+    pub fn flagged_nodes(&self) -> Vec<GameTreeIndex> {
+        (0..self.nodes.len())
+            .filter(|node| {
+                self.hotspot(*node).is_some()
+                    || matches!(self.move_annotation(*node), Some(MoveAnnotation::BadMove(_)))
+            })
+            .collect()
+    }
+
+    fn find_unknown_value(&self, node: GameTreeIndex, identifier: &str) -> Option<&str> {
+        self.nodes[node].get_unknown_values(identifier).into_iter().next()
+    }
+}
+
+fn has_identifier(token: &SgfToken, identifiers: &[&str]) -> bool {
+    match token {
+        SgfToken::Unknown((ident, _)) | SgfToken::Invalid((ident, _)) => identifiers.contains(&ident.as_str()),
+        _ => false,
+    }
+}
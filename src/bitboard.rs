@@ -0,0 +1,212 @@
+/// Number of words stored inline, enough for boards up to 25x25
+const INLINE_WORDS: usize = 10;
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+enum Words {
+    Inline([u64; INLINE_WORDS]),
+    Heap(Vec<u64>),
+}
+
+/// One bit for every point of a board, row by row from the top left.
+///
+/// Boards up to 25x25 are stored inline, so cloning does not allocate. Larger boards, up to the
+/// 52x52 SGF allows, are stored on the heap.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct BitBoard {
+    words: Words,
+    width: u32,
+    height: u32,
+}
+
+impl BitBoard {
+    pub fn new(width: u32, height: u32) -> BitBoard {
+        let count = ((width * height) as usize).div_ceil(64);
+        let words = if count <= INLINE_WORDS {
+            Words::Inline([0; INLINE_WORDS])
+        } else {
+            Words::Heap(vec![0; count])
+        };
+        BitBoard { words, width, height }
+    }
+
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Checks if the point at `index`, counted row by row from the top left, is set
+    pub fn get(&self, index: usize) -> bool {
+        self.words()[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    pub fn set(&mut self, index: usize) {
+        self.words_mut()[index / 64] |= 1 << (index % 64);
+    }
+
+    pub fn clear(&mut self, index: usize) {
+        self.words_mut()[index / 64] &= !(1 << (index % 64));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words().iter().all(|word| *word == 0)
+    }
+
+    pub fn count(&self) -> u32 {
+        self.words().iter().map(|word| word.count_ones()).sum()
+    }
+
+    /// Gets the indices of all set points, in order
+    pub fn indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words().iter().enumerate().flat_map(|(i, word)| {
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(i * 64 + bit)
+            })
+        })
+    }
+
+    pub fn and(&self, other: &BitBoard) -> BitBoard {
+        self.combine(other, |a, b| a & b)
+    }
+
+    pub fn or(&self, other: &BitBoard) -> BitBoard {
+        self.combine(other, |a, b| a | b)
+    }
+
+    /// Gets the points set in `self` but not in `other`
+    pub fn and_not(&self, other: &BitBoard) -> BitBoard {
+        self.combine(other, |a, b| a & !b)
+    }
+
+    /// Gets every point on the board that is not set
+    pub fn not(&self) -> BitBoard {
+        let mut out = self.clone();
+        out.words_mut().iter_mut().for_each(|word| *word = !*word);
+        out.trim();
+        out
+    }
+
+    /// Gets the points next to any set point, not including the set points themselves
+    pub fn neighbours(&self) -> BitBoard {
+        self.expand(&self.wrap_masks()).and_not(self)
+    }
+
+    /// Gets the connected group of points in `mask` that includes the points set in `self`
+    pub fn flood(&self, mask: &BitBoard) -> BitBoard {
+        let wrap_masks = self.wrap_masks();
+        let mut area = self.and(mask);
+        loop {
+            let grown = area.expand(&wrap_masks).and(mask);
+            if grown == area {
+                return area;
+            }
+            area = grown;
+        }
+    }
+
+    /// Gets the set points and all their neighbours
+    fn expand(&self, (not_first, not_last): &(BitBoard, BitBoard)) -> BitBoard {
+        let width = self.width as usize;
+        let mut out = self.clone();
+        let mut shifted = self.clone();
+        // Points moved a step left or right wrap around to the other end of a row, so those are
+        // masked out
+        shifted.shift_down(1);
+        out.or_assign(&shifted.and(not_last));
+        shifted.clone_from(self);
+        shifted.shift_up(1);
+        out.or_assign(&shifted.and(not_first));
+        shifted.clone_from(self);
+        shifted.shift_down(width);
+        out.or_assign(&shifted);
+        shifted.clone_from(self);
+        shifted.shift_up(width);
+        out.or_assign(&shifted);
+        out.trim();
+        out
+    }
+
+    /// Gets the boards with every point set except those in the first column, and except those
+    /// in the last column
+    fn wrap_masks(&self) -> (BitBoard, BitBoard) {
+        let mut not_first = BitBoard::new(self.width, self.height).not();
+        let mut not_last = not_first.clone();
+        if self.width == 0 {
+            return (not_first, not_last);
+        }
+        for y in 0..self.height {
+            not_first.clear((y * self.width) as usize);
+            not_last.clear((y * self.width + self.width - 1) as usize);
+        }
+        (not_first, not_last)
+    }
+
+    /// Moves all bits towards higher indices
+    fn shift_up(&mut self, bits: usize) {
+        let words = self.words_mut();
+        let (offset, shift) = (bits / 64, bits % 64);
+        for i in (0..words.len()).rev() {
+            let low = if i >= offset { words[i - offset] } else { 0 };
+            let carry = if shift > 0 && i > offset { words[i - offset - 1] >> (64 - shift) } else { 0 };
+            words[i] = (low << shift) | carry;
+        }
+    }
+
+    /// Moves all bits towards lower indices
+    fn shift_down(&mut self, bits: usize) {
+        let words = self.words_mut();
+        let (offset, shift) = (bits / 64, bits % 64);
+        let len = words.len();
+        for i in 0..len {
+            let high = if i + offset < len { words[i + offset] } else { 0 };
+            let carry = if shift > 0 && i + offset + 1 < len { words[i + offset + 1] << (64 - shift) } else { 0 };
+            words[i] = (high >> shift) | carry;
+        }
+    }
+
+    fn or_assign(&mut self, other: &BitBoard) {
+        for (word, other) in self.words_mut().iter_mut().zip(other.words()) {
+            *word |= *other;
+        }
+    }
+
+    fn combine(&self, other: &BitBoard, f: impl Fn(u64, u64) -> u64) -> BitBoard {
+        let mut out = self.clone();
+        for (word, other) in out.words_mut().iter_mut().zip(other.words()) {
+            *word = f(*word, *other);
+        }
+        out
+    }
+
+    /// Clears the bits past the last point of the board
+    fn trim(&mut self) {
+        let points = (self.width * self.height) as usize;
+        let words = self.words_mut();
+        for (i, word) in words.iter_mut().enumerate() {
+            let start = i * 64;
+            if start >= points {
+                *word = 0;
+            } else if points - start < 64 {
+                *word &= (1 << (points - start)) - 1;
+            }
+        }
+    }
+
+    fn words(&self) -> &[u64] {
+        match self.words {
+            Words::Inline(ref words) => words,
+            Words::Heap(ref words) => words,
+        }
+    }
+
+    fn words_mut(&mut self) -> &mut [u64] {
+        match self.words {
+            Words::Inline(ref mut words) => words,
+            Words::Heap(ref mut words) => words,
+        }
+    }
+}
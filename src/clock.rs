@@ -0,0 +1,438 @@
+use crate::{GameTree, BadukError, BadukErrorKind, Color, Position, SgfToken};
+use crate::game::GameTreeIndex;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Time control of a game, stored as `TM` and `OT` on the root node
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeControl {
+    /// Only main time, the game is lost when it runs out
+    Absolute { main: Duration },
+    /// Japanese byo-yomi. After main time, each move must be played within `period`, or one of the
+    /// `periods` is used up.
+    ByoYomi { main: Duration, period: Duration, periods: u32 },
+    /// After main time, `stones` moves must be played within each `period`
+    Canadian { main: Duration, period: Duration, stones: u32 },
+    /// `increment` is added to the time left after each move
+    Fischer { main: Duration, increment: Duration },
+}
+
+impl TimeControl {
+    pub fn main_time(&self) -> Duration {
+        match self {
+            TimeControl::Absolute { main }
+            | TimeControl::ByoYomi { main, .. }
+            | TimeControl::Canadian { main, .. }
+            | TimeControl::Fischer { main, .. } => *main,
+        }
+    }
+
+    /// Gets the time each player starts with
+    pub fn initial_time(&self) -> TimeLeft {
+        let (period, periods) = match self {
+            TimeControl::ByoYomi { period, periods, .. } => (*period, *periods),
+            TimeControl::Canadian { period, stones, .. } => (*period, *stones),
+            _ => (Duration::from_secs(0), 0),
+        };
+        TimeLeft {
+            main: self.main_time(),
+            period,
+            periods,
+            timed_out: false,
+        }
+    }
+
+    /// Parses the overtime description of `OT`, like "5x30 byo-yomi", "25/600 Canadian" or
+    /// "10 fischer", for a game with the given main time. Without a description the time is
+    /// absolute.
+    pub fn from_overtime(main: Duration, overtime: Option<&str>) -> Result<TimeControl, BadukError> {
+        let overtime = match overtime.map(str::trim) {
+            None | Some("") => return Ok(TimeControl::Absolute { main }),
+            Some(overtime) => overtime.to_lowercase(),
+        };
+        let mut words = overtime.split_whitespace();
+        let (amount, kind) = (words.next().unwrap_or_default(), words.next().unwrap_or_default());
+        let invalid = || BadukError::from(BadukErrorKind::InvalidInput);
+        let split = |separator: char| {
+            let mut parts = amount.splitn(2, separator);
+            let count = parts.next().and_then(|count| count.parse().ok());
+            let time = parts.next().and_then(parse_seconds);
+            count.zip(time).ok_or_else(invalid)
+        };
+        match kind {
+            "byo-yomi" | "byoyomi" => {
+                let (periods, period) = split('x')?;
+                Ok(TimeControl::ByoYomi { main, period, periods })
+            }
+            "canadian" => {
+                let (stones, period) = split('/')?;
+                Ok(TimeControl::Canadian { main, period, stones })
+            }
+            "fischer" => {
+                let increment = parse_seconds(amount).ok_or_else(invalid)?;
+                Ok(TimeControl::Fischer { main, increment })
+            }
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Gets the overtime description written as `OT`
+    pub fn overtime(&self) -> Option<String> {
+        match self {
+            TimeControl::Absolute { .. } => None,
+            TimeControl::ByoYomi { period, periods, .. } => {
+                Some(format!("{}x{} byo-yomi", periods, format_seconds(*period)))
+            }
+            TimeControl::Canadian { period, stones, .. } => {
+                Some(format!("{}/{} Canadian", stones, format_seconds(*period)))
+            }
+            TimeControl::Fischer { increment, .. } => Some(format!("{} fischer", format_seconds(*increment))),
+        }
+    }
+
+    /// Takes time used for a move from a player's time. The player runs out of time when the move
+    /// takes longer than the time they have left.
+    fn charge(&self, time: &mut TimeLeft, elapsed: Duration) {
+        let mut rest = elapsed.saturating_sub(time.main);
+        time.main = time.main.saturating_sub(elapsed);
+        match self {
+            TimeControl::ByoYomi { period, .. } => {
+                while rest > time.period && !time.timed_out {
+                    rest -= time.period;
+                    time.periods = time.periods.saturating_sub(1);
+                    time.period = if time.periods == 0 { Duration::from_secs(0) } else { *period };
+                    time.timed_out = time.periods == 0;
+                }
+                time.period = time.period.saturating_sub(rest);
+            }
+            TimeControl::Canadian { .. } => {
+                time.timed_out = rest > time.period;
+                time.period = time.period.saturating_sub(rest);
+            }
+            TimeControl::Absolute { .. } | TimeControl::Fischer { .. } => {
+                time.timed_out = rest > Duration::from_secs(0);
+            }
+        }
+    }
+
+    /// Updates a player's time once their move is played
+    fn finish_move(&self, time: &mut TimeLeft) {
+        let in_overtime = time.main == Duration::from_secs(0);
+        match self {
+            TimeControl::ByoYomi { period, .. } if in_overtime => time.period = *period,
+            TimeControl::Canadian { period, stones, .. } if in_overtime => {
+                time.periods = time.periods.saturating_sub(1);
+                if time.periods == 0 {
+                    time.period = *period;
+                    time.periods = *stones;
+                }
+            }
+            TimeControl::Fischer { increment, .. } => time.main += *increment,
+            _ => {}
+        }
+    }
+
+    /// Gets the tokens for the time left of a player, `BL` or `WL` with the time left in the
+    /// current period or main time, and `OB` or `OW` with the periods or stones left in overtime
+    fn time_tokens(&self, color: Color, time: &TimeLeft) -> Vec<SgfToken> {
+        let in_overtime = time.main == Duration::from_secs(0);
+        match self {
+            TimeControl::ByoYomi { .. } | TimeControl::Canadian { .. } if in_overtime => vec![
+                time_token(color, time.period),
+                SgfToken::MovesRemaining { color, moves: time.periods },
+            ],
+            _ => vec![time_token(color, time.main)],
+        }
+    }
+}
+
+impl fmt::Display for TimeControl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", format_seconds(self.main_time()))?;
+        match self.overtime() {
+            Some(overtime) => write!(f, " + {}", overtime),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The time a player has left
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeLeft {
+    pub main: Duration,
+    /// Time left in the current overtime period
+    pub period: Duration,
+    /// Byo-yomi periods left, or moves left to play in the current Canadian period
+    pub periods: u32,
+    pub timed_out: bool,
+}
+
+/// Something that tells the time, as the time passed since some fixed point. Functions returning a
+/// `Duration` can be used as time sources, for example to drive a clock in tests.
+pub trait TimeSource {
+    fn now(&self) -> Duration;
+}
+
+impl<F: Fn() -> Duration> TimeSource for F {
+    fn now(&self) -> Duration {
+        self()
+    }
+}
+
+/// Tells the time using the monotonic clock of the system
+#[derive(Debug, Clone, Copy)]
+pub struct SystemTimeSource {
+    start: Instant,
+}
+
+impl Default for SystemTimeSource {
+    fn default() -> SystemTimeSource {
+        SystemTimeSource { start: Instant::now() }
+    }
+}
+
+impl TimeSource for SystemTimeSource {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// A game clock for both players.
+///
+/// Only one clock runs at a time. Playing a move through the clock stops the clock of the player,
+/// records their time left on the new node and starts the clock of the other player.
+#[derive(Debug, Clone)]
+pub struct Clock<T: TimeSource = SystemTimeSource> {
+    control: TimeControl,
+    source: T,
+    black: TimeLeft,
+    white: TimeLeft,
+    /// The player whose clock runs, and when it was started
+    running: Option<(Color, Duration)>,
+}
+
+impl Clock<SystemTimeSource> {
+    pub fn new(control: TimeControl) -> Clock<SystemTimeSource> {
+        Clock::with_time_source(control, SystemTimeSource::default())
+    }
+}
+
+impl<T: TimeSource> Clock<T> {
+    pub fn with_time_source(control: TimeControl, source: T) -> Clock<T> {
+        Clock {
+            control,
+            source,
+            black: control.initial_time(),
+            white: control.initial_time(),
+            running: None,
+        }
+    }
+
+    pub fn time_control(&self) -> TimeControl {
+        self.control
+    }
+
+    /// Gets the player whose clock runs
+    pub fn running(&self) -> Option<Color> {
+        self.running.map(|(color, _)| color)
+    }
+
+    /// Starts the clock of a player, stopping the other clock
+    pub fn start(&mut self, color: Color) {
+        self.stop();
+        self.running = Some((color, self.source.now()));
+    }
+
+    /// Stops the running clock, keeping the time used so far
+    pub fn stop(&mut self) {
+        if let Some((color, started)) = self.running.take() {
+            let elapsed = self.source.now().saturating_sub(started);
+            let mut time = *self.time(color);
+            self.control.charge(&mut time, elapsed);
+            *self.time_mut(color) = time;
+        }
+    }
+
+    /// Gets the time a player has left right now
+    pub fn time_left(&self, color: Color) -> TimeLeft {
+        let mut time = *self.time(color);
+        if let Some((running, started)) = self.running {
+            if running == color {
+                self.control.charge(&mut time, self.source.now().saturating_sub(started));
+            }
+        }
+        time
+    }
+
+    /// Gets the player who ran out of time, if any
+    pub fn flagged(&self) -> Option<Color> {
+        [Color::Black, Color::White]
+            .iter()
+            .copied()
+            .find(|color| self.time_left(*color).timed_out)
+    }
+
+    /// Plays a move for the player whose clock runs, see `press`
+    pub fn play_move(&mut self, game: &mut GameTree, pos: impl Into<Position>) -> Result<GameTreeIndex, BadukError> {
+        let pos = pos.into();
+        self.press(game, |game, color| game.play_move(pos, color))
+    }
+
+    /// Passes for the player whose clock runs, see `press`
+    pub fn pass(&mut self, game: &mut GameTree) -> Result<GameTreeIndex, BadukError> {
+        self.press(game, |game, color| game.pass(color))
+    }
+
+    /// Ends the game with a win on time if the player whose clock runs has run out of time
+    pub fn check_time(&mut self, game: &mut GameTree) -> Result<Option<Color>, BadukError> {
+        match self.running {
+            Some((color, _)) if self.time_left(color).timed_out => {
+                self.stop();
+                game.time_out(color)?;
+                Ok(Some(color))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Adds a node to the game for the player whose clock runs, and records their time left on
+    /// it, `BL`, `WL`, `OB` and `OW`, before starting the clock of the other player. If they ran
+    /// out of time the game ends with a win on time for the other player instead. Illegal moves
+    /// leave the clock running.
+    fn press(
+        &mut self,
+        game: &mut GameTree,
+        add_node: impl FnOnce(&mut GameTree, Color) -> Result<GameTreeIndex, BadukError>,
+    ) -> Result<GameTreeIndex, BadukError> {
+        let color = match self.running {
+            Some((color, _)) => color,
+            None => return Err(BadukErrorKind::ClockStopped.into()),
+        };
+        if self.check_time(game)?.is_some() {
+            return Err(BadukErrorKind::TimedOut.into());
+        }
+        let node = add_node(game, color)?;
+        self.stop();
+        let mut time = *self.time(color);
+        self.control.finish_move(&mut time);
+        *self.time_mut(color) = time;
+        for token in self.control.time_tokens(color, &time) {
+            let ident = sgf_identifier(&token);
+            game.replace_token(node, |t| sgf_identifier(t) == ident, Some(token));
+        }
+        self.start(!color);
+        Ok(node)
+    }
+
+    fn time(&self, color: Color) -> &TimeLeft {
+        match color {
+            Color::Black => &self.black,
+            Color::White => &self.white,
+        }
+    }
+
+    fn time_mut(&mut self, color: Color) -> &mut TimeLeft {
+        match color {
+            Color::Black => &mut self.black,
+            Color::White => &mut self.white,
+        }
+    }
+}
+
+impl GameTree {
+    /// Reads the time control from `TM` and `OT` on the root node
+    pub fn time_control(&self) -> Option<TimeControl> {
+        let root = &self.nodes[self.root];
+        let main = root.tokens.iter().find_map(|token| match token {
+            SgfToken::TimeLimit(time) => Some(Duration::from_secs(u64::from(*time))),
+            SgfToken::Unknown((ident, value)) if ident == "TM" => parse_seconds(value),
+            _ => None,
+        })?;
+        let overtime = root.tokens.iter().find_map(|token| match token {
+            SgfToken::Overtime(overtime) => Some(overtime.as_str()),
+            _ => None,
+        });
+        TimeControl::from_overtime(main, overtime).ok()
+    }
+
+    pub fn set_time_control(&mut self, control: Option<TimeControl>) {
+        let root = self.root;
+        self.replace_token(
+            root,
+            |token| sgf_identifier(token) == "TM",
+            control.map(|control| match seconds_token(control.main_time()) {
+                Ok(time) => SgfToken::TimeLimit(time),
+                Err(value) => SgfToken::Unknown(("TM".to_string(), value)),
+            }),
+        );
+        self.replace_token(
+            root,
+            |token| matches!(token, SgfToken::Overtime(_)),
+            control.and_then(|control| control.overtime()).map(SgfToken::Overtime),
+        );
+    }
+
+    /// Gets the time left for a player recorded on a node, as the time from `BL` or `WL` and the
+    /// periods or stones left in overtime from `OB` or `OW`
+    pub fn recorded_time(&self, node: GameTreeIndex, color: Color) -> Option<(Duration, Option<u32>)> {
+        let time_ident = match color {
+            Color::Black => "BL",
+            Color::White => "WL",
+        };
+        let tokens = &self.nodes[node].tokens;
+        let time = tokens.iter().find_map(|token| match token {
+            SgfToken::Time { color: c, time } if *c == color => Some(Duration::from_secs(u64::from(*time))),
+            SgfToken::Unknown((ident, value)) if ident == time_ident => parse_seconds(value),
+            _ => None,
+        })?;
+        let moves = tokens.iter().find_map(|token| match token {
+            SgfToken::MovesRemaining { color: c, moves } if *c == color => Some(*moves),
+            _ => None,
+        });
+        Some((time, moves))
+    }
+}
+
+fn time_token(color: Color, time: Duration) -> SgfToken {
+    match seconds_token(time) {
+        Ok(time) => SgfToken::Time { color, time },
+        Err(value) => {
+            let ident = match color {
+                Color::Black => "BL",
+                Color::White => "WL",
+            };
+            SgfToken::Unknown((ident.to_string(), value))
+        }
+    }
+}
+
+/// Gets whole seconds as a number for the typed tokens, or the value to write for other times
+fn seconds_token(time: Duration) -> Result<u32, String> {
+    if time.subsec_millis() == 0 && time.as_secs() <= u64::from(u32::MAX) {
+        Ok(time.as_secs() as u32)
+    } else {
+        Err(format_seconds(time))
+    }
+}
+
+/// Writes a time in seconds, with up to millisecond precision
+fn format_seconds(time: Duration) -> String {
+    if time.subsec_millis() == 0 {
+        time.as_secs().to_string()
+    } else {
+        format!("{}", time.as_millis() as f64 / 1000.0)
+    }
+}
+
+fn parse_seconds(value: &str) -> Option<Duration> {
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+        .map(Duration::from_secs_f64)
+}
+
+fn sgf_identifier(token: &SgfToken) -> String {
+    crate::sgf::token_to_property(token).0
+}
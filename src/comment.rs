@@ -0,0 +1,94 @@
+use crate::{GameTree, SgfToken};
+use crate::game::{GameTreeIndex, GameTreeNode};
+use crate::sgf::{escape_text, unescape_text};
+use regex::Regex;
+
+/// Something that can be searched for in comments and node names
+pub trait TextPattern {
+    fn is_match(&self, text: &str) -> bool;
+}
+
+/// Plain strings match case-insensitively anywhere in the text
+impl TextPattern for &str {
+    fn is_match(&self, text: &str) -> bool {
+        text.to_lowercase().contains(&self.to_lowercase())
+    }
+}
+
+impl TextPattern for String {
+    fn is_match(&self, text: &str) -> bool {
+        self.as_str().is_match(text)
+    }
+}
+
+impl TextPattern for Regex {
+    fn is_match(&self, text: &str) -> bool {
+        Regex::is_match(self, text)
+    }
+}
+
+impl TextPattern for &Regex {
+    fn is_match(&self, text: &str) -> bool {
+        Regex::is_match(self, text)
+    }
+}
+
+impl GameTreeNode {
+    /// Gets the comment, `C`, of the node
+    pub fn comment(&self) -> Option<&str> {
+        self.tokens.iter().find_map(|token| match token {
+            SgfToken::Comment(comment) => Some(comment.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn set_comment(&mut self, comment: Option<&str>) {
+        self.replace_token(
+            |token| matches!(token, SgfToken::Comment(_)),
+            comment.map(|comment| SgfToken::Comment(comment.to_string())),
+        );
+    }
+
+    /// Gets the name, `N`, of the node
+    pub fn name(&self) -> Option<String> {
+        self.get_unknown_values("N").into_iter().next().map(unescape_text)
+    }
+
+    pub fn set_name(&mut self, name: Option<&str>) {
+        self.replace_token(
+            |token| matches!(token, SgfToken::Unknown((ident, _)) if ident == "N"),
+            name.map(|name| SgfToken::Unknown(("N".to_string(), escape_text(name)))),
+        );
+    }
+
+    /// Checks if the comment or the name of the node matches a pattern
+    pub fn matches_text(&self, pattern: &impl TextPattern) -> bool {
+        self.comment().map(|comment| pattern.is_match(comment)).unwrap_or(false)
+            || self.name().map(|name| pattern.is_match(&name)).unwrap_or(false)
+    }
+}
+
+impl GameTree {
+    /// Finds all nodes where the comment or node name matches a pattern, either a plain string or
+    /// a `Regex`
+    pub fn search_comments(&self, pattern: impl TextPattern) -> Vec<GameTreeIndex> {
+        (0..self.nodes.len())
+            .filter(|node| self.nodes[*node].matches_text(&pattern))
+            .collect()
+    }
+}
+
+/// Finds all nodes in a collection of games where the comment or node name matches a pattern.
+/// Results are given as pairs of game index and node index.
+pub fn search_comments(games: &[GameTree], pattern: impl TextPattern) -> Vec<(usize, GameTreeIndex)> {
+    games
+        .iter()
+        .enumerate()
+        .flat_map(|(game, tree)| {
+            (0..tree.nodes.len())
+                .filter(|node| tree.nodes[*node].matches_text(&pattern))
+                .map(move |node| (game, node))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
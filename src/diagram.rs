@@ -0,0 +1,160 @@
+use crate::{GameState, GameTree, BadukError, BadukErrorKind, Color, Notation, Position, SgfToken, SvgRenderer, star_points};
+use crate::game::GameTreeIndex;
+use crate::position::column_label;
+use crate::render::row_label;
+use sgf_parser::Action;
+use std::fmt;
+
+/// A note for a move that can not be shown on the diagram itself
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DiagramNote {
+    /// A move played on the point of an earlier numbered move, like "7 at 3"
+    AtMove(u32, u32),
+    /// A move played on a point that held a stone in the initial position, like "7 at C3"
+    AtPoint(u32, Position),
+    Pass(u32),
+}
+
+/// A sequence of moves drawn on a single board, for printing commentary.
+///
+/// The initial position is shown without numbers. Each move is numbered on the point it was
+/// played, and stones captured during the sequence stay on the diagram. Moves on points that
+/// already show a stone are listed as notes instead.
+#[derive(Debug, Clone)]
+pub struct Diagram {
+    /// The initial position, with the stones of the numbered moves added
+    pub board: GameState,
+    pub moves: Vec<(u32, Color, Option<Position>)>,
+    pub numbers: Vec<(Position, u32)>,
+    pub notes: Vec<DiagramNote>,
+}
+
+impl Diagram {
+    /// Gets a renderer for drawing the diagram as an SVG image, with the notes below the board
+    pub fn svg(&self) -> SvgRenderer<'_> {
+        self.board.svg().move_numbers(self.numbers.clone()).caption(self.captions())
+    }
+
+    /// Gets the lines of text describing the moves, listing the numbers played by each color and
+    /// the notes
+    pub fn captions(&self) -> Vec<String> {
+        let mut lines = vec![];
+        for (color, name) in &[(Color::Black, "Black"), (Color::White, "White")] {
+            let numbers: Vec<String> = self
+                .moves
+                .iter()
+                .filter(|(_, c, _)| c == color)
+                .map(|(number, _, _)| number.to_string())
+                .collect();
+            if !numbers.is_empty() {
+                lines.push(format!("{}: {}", name, numbers.join(", ")));
+            }
+        }
+        lines.extend(self.notes.iter().map(|note| self.describe(note)));
+        lines
+    }
+
+    fn describe(&self, note: &DiagramNote) -> String {
+        match note {
+            DiagramNote::AtMove(number, earlier) => format!("{} at {}", number, earlier),
+            DiagramNote::AtPoint(number, pos) => {
+                let point = self.board.coordinates().format(*pos, Notation::Gtp).unwrap_or_else(|_| pos.to_string());
+                format!("{} at {}", number, point)
+            }
+            DiagramNote::Pass(number) => format!("{} pass", number),
+        }
+    }
+}
+
+/// Writes the diagram as text, with numbers on the board and the captions below it
+impl fmt::Display for Diagram {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (width, height) = self.board.dimensions();
+        let stars = star_points(width, height);
+        let cell = self
+            .numbers
+            .iter()
+            .map(|(_, number)| number.to_string().len())
+            .chain((1..=width).map(|x| column_label(x).map_or(0, |label| label.len())))
+            .max()
+            .unwrap_or(1)
+            + 1;
+        let margin = height.to_string().len();
+
+        let header: String = (1..=width).map(|x| format!("{:>cell$}", column_label(x).unwrap_or_default(), cell = cell)).collect();
+        writeln!(f, "{:margin$}{}", "", header, margin = margin)?;
+        for y in 1..=height {
+            let label = row_label(y, height);
+            write!(f, "{:>margin$}", label, margin = margin)?;
+            for x in 1..=width {
+                let pos = Position::from((x, y));
+                let number = self.numbers.iter().find(|(p, _)| *p == pos).map(|(_, n)| n.to_string());
+                let symbol = match (number, self.board.get_stone(pos)) {
+                    (Some(number), _) => number,
+                    (None, Some(Color::Black)) => "X".to_string(),
+                    (None, Some(Color::White)) => "O".to_string(),
+                    (None, None) if stars.contains(&pos) => "+".to_string(),
+                    (None, None) => ".".to_string(),
+                };
+                write!(f, "{:>cell$}", symbol, cell = cell)?;
+            }
+            writeln!(f, " {}", label)?;
+        }
+        writeln!(f, "{:margin$}{}", "", header, margin = margin)?;
+        for line in self.captions() {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+impl GameTree {
+    /// Builds a diagram of the moves played after `from` down to `to`, which must be a node below
+    /// it. The first move is numbered `first_number`, and the position at `from` is shown as the
+    /// initial position.
+    pub fn diagram(&self, from: GameTreeIndex, to: GameTreeIndex, first_number: u32) -> Result<Diagram, BadukError> {
+        let path = self.path_to(to);
+        let start = match path.iter().position(|node| *node == from) {
+            Some(start) => start,
+            None => return Err(BadukErrorKind::InvalidInput.into()),
+        };
+        let mut board = match self.state(from) {
+            Some(state) => state.into_owned(),
+            None => return Err(BadukErrorKind::MissingGoBoard.into()),
+        };
+        let mut diagram_moves = vec![];
+        let mut numbers: Vec<(Position, u32)> = vec![];
+        let mut notes = vec![];
+        let mut number = first_number;
+        for node in &path[start + 1..] {
+            for token in &self.nodes[*node].tokens {
+                let (color, pos) = match token {
+                    SgfToken::Move { color, action: Action::Move(x, y) } if board.is_valid_position((*x, *y)) => {
+                        (*color, Some(Position::from((*x, *y))))
+                    }
+                    SgfToken::Move { color, .. } => (*color, None),
+                    _ => continue,
+                };
+                diagram_moves.push((number, color, pos));
+                match pos {
+                    None => notes.push(DiagramNote::Pass(number)),
+                    Some(pos) => match numbers.iter().find(|(p, _)| *p == pos) {
+                        Some((_, earlier)) => notes.push(DiagramNote::AtMove(number, *earlier)),
+                        None if board.get_stone(pos).is_some() => notes.push(DiagramNote::AtPoint(number, pos)),
+                        None => {
+                            board = board.add_stone(pos, color)?;
+                            numbers.push((pos, number));
+                        }
+                    },
+                }
+                number += 1;
+            }
+        }
+        Ok(Diagram {
+            board,
+            moves: diagram_moves,
+            numbers,
+            notes,
+        })
+    }
+}
@@ -30,6 +30,20 @@ pub enum BadukErrorKind {
     InvalidRootNode,
     #[display(fmt = "Invalid input")]
     InvalidInput,
+    #[display(fmt = "Invalid handicap")]
+    InvalidHandicap(u32),
+    #[display(fmt = "Board is not empty")]
+    BoardNotEmpty,
+    #[display(fmt = "No move on node")]
+    MissingMove,
+    #[display(fmt = "Invalid coordinate")]
+    InvalidCoordinate(String),
+    #[display(fmt = "Game is over")]
+    GameOver,
+    #[display(fmt = "Clock is not running")]
+    ClockStopped,
+    #[display(fmt = "Time ran out")]
+    TimedOut,
 }
 
 impl Error for BadukError {
@@ -102,4 +116,53 @@ impl BadukError {
             source: Some(Box::new(err)),
         }
     }
+
+    pub fn invalid_handicap(stones: u32, err: impl Error + Send + Sync + 'static) -> Self {
+        BadukError {
+            kind: BadukErrorKind::InvalidHandicap(stones),
+            source: Some(Box::new(err)),
+        }
+    }
+
+    pub fn board_not_empty(err: impl Error + Send + Sync + 'static) -> Self {
+        BadukError {
+            kind: BadukErrorKind::BoardNotEmpty,
+            source: Some(Box::new(err)),
+        }
+    }
+
+    pub fn missing_move(err: impl Error + Send + Sync + 'static) -> Self {
+        BadukError {
+            kind: BadukErrorKind::MissingMove,
+            source: Some(Box::new(err)),
+        }
+    }
+
+    pub fn invalid_coordinate(coordinate: &str, err: impl Error + Send + Sync + 'static) -> Self {
+        BadukError {
+            kind: BadukErrorKind::InvalidCoordinate(coordinate.to_string()),
+            source: Some(Box::new(err)),
+        }
+    }
+
+    pub fn game_over(err: impl Error + Send + Sync + 'static) -> Self {
+        BadukError {
+            kind: BadukErrorKind::GameOver,
+            source: Some(Box::new(err)),
+        }
+    }
+
+    pub fn clock_stopped(err: impl Error + Send + Sync + 'static) -> Self {
+        BadukError {
+            kind: BadukErrorKind::ClockStopped,
+            source: Some(Box::new(err)),
+        }
+    }
+
+    pub fn timed_out(err: impl Error + Send + Sync + 'static) -> Self {
+        BadukError {
+            kind: BadukErrorKind::TimedOut,
+            source: Some(Box::new(err)),
+        }
+    }
 }
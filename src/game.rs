@@ -1,13 +1,20 @@
-use crate::{GameState, BadukError, BadukErrorKind, Position, Color, SgfToken};
-use sgf_parser::{GameTree as SgfTree, parse};
+use crate::{GameState, BadukError, BadukErrorKind, Position, Color, SgfToken, fixed_handicap_positions};
+use crate::sgf;
+use sgf_parser::{GameTree as SgfTree, Action};
+use std::borrow::Cow;
 use std::convert::TryFrom;
 
 pub type GameTreeIndex = usize;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GameTreeNode {
     pub parent: Option<GameTreeIndex>,
+    /// The board after the node, if stored. Trees that only keep checkpoints leave this empty for
+    /// most nodes, see `GameTree::state`.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
     pub state: Option<GameState>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialize::tokens"))]
     pub tokens: Vec<SgfToken>,
     pub children: Vec<GameTreeIndex>,
     // active: bool -> used to indicate active branch
@@ -22,6 +29,39 @@ impl GameTreeNode {
             children: vec![],
         }
     }
+
+    /// Replaces the first matching token, keeping its place in the token list. Any other matching
+    /// tokens are removed.
+    pub(crate) fn replace_token(&mut self, matches: impl Fn(&SgfToken) -> bool, token: Option<SgfToken>) {
+        let index = self.tokens.iter().position(&matches);
+        self.tokens.retain(|t| !matches(t));
+        match (index, token) {
+            (Some(index), Some(token)) => self.tokens.insert(index, token),
+            (None, Some(token)) => self.tokens.push(token),
+            _ => {}
+        }
+    }
+
+    /// Gets all tokens for properties that are not modelled by `SgfToken`, in the order they were
+    /// added
+    pub fn get_unknown_tokens(&self) -> Vec<&SgfToken> {
+        self.tokens
+            .iter()
+            .filter(|token| matches!(token, SgfToken::Unknown(_)))
+            .collect()
+    }
+
+    /// Gets the raw values of all tokens with the given identifier that are not modelled by
+    /// `SgfToken`
+    pub fn get_unknown_values(&self, identifier: &str) -> Vec<&str> {
+        self.tokens
+            .iter()
+            .filter_map(|token| match token {
+                SgfToken::Unknown((ident, value)) if ident == identifier => Some(value.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug)]
@@ -29,6 +69,8 @@ pub struct GameTree {
     pub root: GameTreeIndex,
     pub nodes: Vec<GameTreeNode>,
     pub current: GameTreeIndex,
+    checkpoint_interval: Option<usize>,
+    pub(crate) strict: bool,
 }
 
 impl Default for GameTree {
@@ -37,7 +79,9 @@ impl Default for GameTree {
         GameTree {
             root: 0,
             current: 0,
-            nodes: vec![root]
+            nodes: vec![root],
+            checkpoint_interval: None,
+            strict: false,
         }
     }
 }
@@ -50,7 +94,9 @@ impl GameTree {
         GameTree {
             root: 0,
             current: 0,
-            nodes: vec![root]
+            nodes: vec![root],
+            checkpoint_interval: None,
+            strict: false,
         }
     }
 
@@ -62,36 +108,172 @@ impl GameTree {
         self.nodes.get(node)
     }
 
+    /// Gets the position of the move played in a node, if any. Passes are not included.
+    pub fn last_move(&self, node: GameTreeIndex) -> Option<Position> {
+        let state = self.stored_state(node)?;
+        self.nodes[node].tokens.iter().find_map(|token| match token {
+            SgfToken::Move { action: Action::Move(x, y), .. } if state.is_valid_position((*x, *y)) => {
+                Some((*x, *y).into())
+            }
+            _ => None,
+        })
+    }
+
+    /// Gets the nodes from the root down to a node, both included
+    pub fn path_to(&self, node: GameTreeIndex) -> Vec<GameTreeIndex> {
+        let mut path = vec![node];
+        while let Some(parent) = self.nodes[*path.last().expect("path is never empty")].parent {
+            path.push(parent);
+        }
+        path.reverse();
+        path
+    }
+
+    /// Gets the moves played from the root down to a node, numbered from 1. Passes have no position.
+    pub fn numbered_moves(&self, node: GameTreeIndex) -> Vec<(u32, Color, Option<Position>)> {
+        self.path_to(node)
+            .into_iter()
+            .flat_map(|index| {
+                self.nodes[index].tokens.iter().filter_map(move |token| match token {
+                    SgfToken::Move { color, .. } => Some((*color, self.last_move(index))),
+                    _ => None,
+                })
+            })
+            .enumerate()
+            .map(|(number, (color, pos))| (number as u32 + 1, color, pos))
+            .collect()
+    }
+
+    /// Gets the indices of all nodes that contain tokens not modelled by `SgfToken`
+    pub fn get_unknown_nodes(&self) -> Vec<GameTreeIndex> {
+        (0..self.nodes.len())
+            .filter(|index| !self.nodes[*index].get_unknown_tokens().is_empty())
+            .collect()
+    }
+
+    /// Gets the stored board of the current node. The current node keeps its board in trees with
+    /// checkpoints too, as long as it is only changed by adding nodes. Use `state` for any node.
     pub fn current_state(&self) -> Option<&GameState> {
         self.nodes[self.current].state.as_ref()
     }
 
-    fn add_node(&mut self, parent: GameTreeIndex, tokens: Vec<SgfToken>, state: GameState) -> GameTreeIndex {
+    /// Gets the board of a node. A node without a stored state is replayed from the nearest
+    /// node above it that has one.
+    pub fn state(&self, node: GameTreeIndex) -> Option<Cow<'_, GameState>> {
+        let mut path = vec![];
+        let mut index = node;
+        let state = loop {
+            match self.nodes[index].state {
+                Some(ref state) => break state,
+                None => {
+                    path.push(index);
+                    index = self.nodes[index].parent?;
+                }
+            }
+        };
+        if path.is_empty() {
+            return Some(Cow::Borrowed(state));
+        }
+        let state = path
+            .iter()
+            .rev()
+            .fold(state.clone(), |state, index| replay_tokens(state, &self.nodes[*index].tokens));
+        Some(Cow::Owned(state))
+    }
+
+    /// Gets the board of a node like `state`, and stores it on the node so it is not replayed again
+    pub fn cache_state(&mut self, node: GameTreeIndex) -> Option<&GameState> {
+        if self.nodes[node].state.is_none() {
+            self.nodes[node].state = self.state(node).map(Cow::into_owned);
+        }
+        self.nodes[node].state.as_ref()
+    }
+
+    pub fn checkpoint_interval(&self) -> Option<usize> {
+        self.checkpoint_interval
+    }
+
+    /// Sets how many boards the tree keeps. With `None`, the default, every node stores its board.
+    /// With an interval, only the root, the current node and the nodes at a multiple of the
+    /// interval below the root keep theirs, and other boards are replayed from the nearest
+    /// checkpoint when needed.
+    pub fn set_checkpoint_interval(&mut self, interval: Option<usize>) {
+        self.checkpoint_interval = interval.map(|interval| interval.max(1));
+        // Parents always come before their children, so the states above a node are final when
+        // it is reached
+        for index in 0..self.nodes.len() {
+            if !self.is_checkpoint(index) && index != self.current {
+                self.nodes[index].state = None;
+            } else if self.nodes[index].state.is_none() {
+                self.nodes[index].state = self.state(index).map(Cow::into_owned);
+            }
+        }
+    }
+
+    /// Drops the boards cached by `cache_state` and by editing nodes, keeping only the checkpoints
+    pub fn release_states(&mut self) {
+        if self.checkpoint_interval.is_some() {
+            self.set_checkpoint_interval(self.checkpoint_interval);
+        }
+    }
+
+    /// Gets the number of nodes above a node
+    pub fn depth(&self, node: GameTreeIndex) -> usize {
+        let mut depth = 0;
+        let mut index = node;
+        while let Some(parent) = self.nodes[index].parent {
+            depth += 1;
+            index = parent;
+        }
+        depth
+    }
+
+    // `usize::is_multiple_of` needs Rust 1.87
+    #[allow(clippy::manual_is_multiple_of)]
+    fn is_checkpoint(&self, node: GameTreeIndex) -> bool {
+        match self.checkpoint_interval {
+            Some(interval) => self.depth(node) % interval == 0,
+            None => true,
+        }
+    }
+
+    /// Drops the board of a node that is not a checkpoint, once a node has been added below it
+    fn release_state(&mut self, node: GameTreeIndex) {
+        if !self.is_checkpoint(node) {
+            self.nodes[node].state = None;
+        }
+    }
+
+    /// Gets the nearest stored board at or above a node, for things that are the same for every
+    /// board in the tree, like its size
+    pub(crate) fn stored_state(&self, node: GameTreeIndex) -> Option<&GameState> {
+        let mut index = node;
+        loop {
+            match self.nodes[index].state {
+                Some(ref state) => return Some(state),
+                None => index = self.nodes[index].parent?,
+            }
+        }
+    }
+
+    fn add_node(&mut self, parent: GameTreeIndex, tokens: Vec<SgfToken>, state: Option<GameState>) -> GameTreeIndex {
         let new_id = self.nodes.len();
         let new_node = GameTreeNode {
             parent: Some(parent),
-            state: Some(state),
+            state,
             tokens,
             children: vec![],
         };
         self.nodes[parent].children.push(new_id);
         self.nodes.push(new_node);
         self.current = new_id;
+        self.release_state(parent);
         new_id
     }
 
     pub fn create_new_node(&mut self, parent: GameTreeIndex) -> GameTreeIndex {
-        let new_id = self.nodes.len();
-        let new_node = GameTreeNode {
-            parent: Some(parent),
-            state: self.nodes[parent].state.clone(),
-            tokens: vec![],
-            children: vec![],
-        };
-        self.nodes[parent].children.push(new_id);
-        self.nodes.push(new_node);
-        self.current = new_id;
-        new_id
+        let state = self.state(parent).map(Cow::into_owned);
+        self.add_node(parent, vec![], state)
     }
 
     pub fn play_move(&mut self, pos: impl Into<Position>, color: Color) -> Result<GameTreeIndex, BadukError> {
@@ -100,91 +282,240 @@ impl GameTree {
 
     pub fn play_move_as_variation(&mut self, pos: impl Into<Position>, color: Color, parent: GameTreeIndex) -> Result<GameTreeIndex, BadukError> {
         let pos = pos.into();
-        let current_node = &self.nodes[parent];
-        match current_node.state {
-            None => Err(BadukErrorKind::MissingGoBoard.into()),
-            Some(ref current_state) => {
-                let mut state = current_state.place_stone(pos, color)?;
-                let removed = state.remove_dead_stones(!color);
-                let valid = state.is_valid();
-                if !valid {
-                    Err(BadukErrorKind::SuicidalMove.into())
-                } else {
-                    if self.is_directly_retaking_ko(color, &state) {
-                        return Err(BadukErrorKind::RetakingKo.into());
-                    }
-                    state.capture_stones(removed.len() as i32, !color);
-                    let tokens = vec![SgfToken::Move {
-                        coordinate: pos.into(),
-                        color,
-                    }];
-                    Ok(self.add_node(parent, tokens, state))
-                }
+        self.check_playable()?;
+        let state = match self.state(parent) {
+            None => return Err(BadukErrorKind::MissingGoBoard.into()),
+            Some(state) => play_on_state(&state, pos, color)?,
+        };
+        let tokens = vec![SgfToken::Move {
+            action: Action::Move(pos.x() as u8, pos.y() as u8),
+            color,
+        }];
+        Ok(self.add_node(parent, tokens, Some(state)))
+    }
+
+    pub fn pass(&mut self, color: Color) -> Result<GameTreeIndex, BadukError> {
+        self.pass_as_variation(color, self.current)
+    }
+
+    /// Adds a node where a player passes. Passing ends the ko restriction.
+    pub fn pass_as_variation(&mut self, color: Color, parent: GameTreeIndex) -> Result<GameTreeIndex, BadukError> {
+        self.check_playable()?;
+        let state = match self.state(parent) {
+            None => return Err(BadukErrorKind::MissingGoBoard.into()),
+            Some(state) => pass_on_state(&state, color),
+        };
+        let tokens = vec![SgfToken::Move { action: Action::Pass, color }];
+        Ok(self.add_node(parent, tokens, Some(state)))
+    }
+
+    /// Applies a pass token, as written, to the board of a node
+    fn pass_on_node(&mut self, token: &SgfToken, color: Color, node: GameTreeIndex) -> Result<GameTreeIndex, BadukError> {
+        let state = match self.state(node) {
+            None => return Err(BadukErrorKind::MissingGoBoard.into()),
+            Some(state) => pass_on_state(&state, color),
+        };
+        self.nodes[node].state = Some(state);
+        Ok(self.add_token(node, token))
+    }
+
+    /// Removes a node and all nodes below it. The remaining nodes keep their order, so the indices
+    /// of nodes added after the removed ones change. If the current node is removed, the parent of
+    /// the removed node becomes current.
+    pub fn remove_node(&mut self, node: GameTreeIndex) -> Result<(), BadukError> {
+        let parent = match self.nodes[node].parent {
+            None => return Err(BadukErrorKind::InvalidRootNode.into()),
+            Some(parent) => parent,
+        };
+        let mut removed = vec![false; self.nodes.len()];
+        let mut pending = vec![node];
+        while let Some(index) = pending.pop() {
+            removed[index] = true;
+            pending.extend(self.nodes[index].children.iter().copied());
+        }
+        let mut new_indices = vec![0; self.nodes.len()];
+        let mut next = 0;
+        for (index, is_removed) in removed.iter().enumerate() {
+            new_indices[index] = next;
+            if !is_removed {
+                next += 1;
             }
         }
+        self.nodes[parent].children.retain(|child| *child != node);
+        let current = if removed[self.current] { parent } else { self.current };
+        let nodes = std::mem::take(&mut self.nodes);
+        self.nodes = nodes
+            .into_iter()
+            .zip(removed)
+            .filter(|(_, removed)| !removed)
+            .map(|(mut node, _)| {
+                node.parent = node.parent.map(|parent| new_indices[parent]);
+                node.children.iter_mut().for_each(|child| *child = new_indices[*child]);
+                node
+            })
+            .collect();
+        self.root = new_indices[self.root];
+        self.current = new_indices[current];
+        self.cache_state(self.current);
+        Ok(())
     }
 
     fn play_move_on_node(&mut self, pos: impl Into<Position>, color: Color, node: GameTreeIndex) -> Result<GameTreeIndex, BadukError> {
         let pos = pos.into();
-        match self.nodes[node].state {
-            None => Err(BadukErrorKind::MissingGoBoard.into()),
-            Some(ref current_state) => {
-                let mut state = current_state.place_stone(pos, color)?;
-                let removed = state.remove_dead_stones(!color);
-                let valid = state.is_valid();
-                if !valid {
-                    Err(BadukErrorKind::SuicidalMove.into())
-                } else {
-                    if self.is_directly_retaking_ko(color, &state) {
-                        return Err(BadukErrorKind::RetakingKo.into());
-                    }
-                    state.capture_stones(removed.len() as i32, !color);
-                    self.nodes[node].tokens.push(SgfToken::Move {
-                        coordinate: pos.into(),
-                        color,
-                    });
-                    self.nodes[node].state = Some(state);
-                    Ok(node)
-                }
-            }
-        }
+        let state = match self.state(node) {
+            None => return Err(BadukErrorKind::MissingGoBoard.into()),
+            Some(state) => play_on_state(&state, pos, color)?,
+        };
+        self.nodes[node].tokens.push(SgfToken::Move {
+            action: Action::Move(pos.x() as u8, pos.y() as u8),
+            color,
+        });
+        self.nodes[node].state = Some(state);
+        Ok(node)
     }
 
     pub fn add_stone(&mut self, pos: impl Into<Position>, color: Color) -> Result<GameTreeIndex, BadukError> {
         self.add_stone_on_node(pos, color, self.current)
     }
 
+    /// Sets up a stone on a node, recorded as `AB` or `AW`. A stone already on the point is
+    /// replaced, as is any other setup of the same point on the node.
     pub fn add_stone_on_node(&mut self, pos: impl Into<Position>, color: Color, node: GameTreeIndex) -> Result<GameTreeIndex, BadukError> {
-        let pos = pos.into();
-        let current_node = &self.nodes[node];
-
-        match current_node.state {
-            None => Err(BadukErrorKind::MissingGoBoard.into()),
-            Some(ref current_state) => {
-                let state = current_state.add_stone(pos, color)?;
-                self.nodes[node].tokens.push(
-                    SgfToken::Add {
-                        coordinate: pos.into(),
-                        color,
-                    });
-                self.nodes[node].state = Some(state);
-                Ok(self.current)
+        self.setup_point(pos.into(), Some(color), node)
+    }
+
+    pub fn erase_stone(&mut self, pos: impl Into<Position>) -> Result<GameTreeIndex, BadukError> {
+        self.erase_stone_on_node(pos, self.current)
+    }
+
+    /// Clears a point on a node, recorded as `AE`. Any other setup of the same point on the node is
+    /// removed.
+    pub fn erase_stone_on_node(&mut self, pos: impl Into<Position>, node: GameTreeIndex) -> Result<GameTreeIndex, BadukError> {
+        self.setup_point(pos.into(), None, node)
+    }
+
+    /// Gets the setup of a node, as positions with the color put there, or `None` for cleared points
+    pub fn get_setup(&self, node: GameTreeIndex) -> Vec<(Position, Option<Color>)> {
+        self.nodes[node].tokens.iter().flat_map(setup_from_token).collect()
+    }
+
+    fn setup_point(&mut self, pos: Position, color: Option<Color>, node: GameTreeIndex) -> Result<GameTreeIndex, BadukError> {
+        self.apply_setup(pos, color, node)?;
+        let mut tokens = vec![];
+        for token in std::mem::take(&mut self.nodes[node].tokens) {
+            let setup = setup_from_token(&token);
+            if setup.iter().all(|(p, _)| *p != pos) {
+                tokens.push(token);
+                continue;
+            }
+            // A compressed point list is split up, keeping its other points
+            tokens.extend(setup.into_iter().filter(|(p, _)| *p != pos).map(|(p, c)| setup_to_token(p, c)));
+        }
+        // The point goes next to the others of the same property, so they stay one property
+        let same_property = tokens
+            .iter()
+            .rposition(|token| setup_from_token(token).first().map(|(_, c)| *c) == Some(color));
+        match same_property {
+            Some(index) => tokens.insert(index + 1, setup_to_token(pos, color)),
+            None => tokens.push(setup_to_token(pos, color)),
+        }
+        self.nodes[node].tokens = tokens;
+        self.refresh_descendants(node);
+        Ok(node)
+    }
+
+    /// Changes the state of a node for a setup property, without touching its tokens
+    fn apply_setup(&mut self, pos: Position, color: Option<Color>, node: GameTreeIndex) -> Result<(), BadukError> {
+        let state = match self.state(node) {
+            None => return Err(BadukErrorKind::MissingGoBoard.into()),
+            Some(state) => apply_setup_to_state(&state, pos, color)?,
+        };
+        self.nodes[node].state = Some(state);
+        Ok(())
+    }
+
+    /// Recomputes the stored states of all nodes below a node, after its setup has changed. Tokens
+    /// that no longer apply to the board are kept, but leave the board as it was.
+    fn refresh_descendants(&mut self, node: GameTreeIndex) {
+        let mut pending = self.nodes[node].children.clone();
+        while let Some(index) = pending.pop() {
+            if self.nodes[index].state.is_some() || self.checkpoint_interval.is_none() {
+                let parent = self.nodes[index].parent.expect("child nodes have a parent");
+                let state = self
+                    .state(parent)
+                    .map(|state| replay_tokens(state.into_owned(), &self.nodes[index].tokens));
+                self.nodes[index].state = state;
             }
+            pending.extend(self.nodes[index].children.iter().copied());
         }
     }
 
-    fn is_directly_retaking_ko(&self, color: Color, new_state: &GameState) -> bool {
-        if let Some(parent_id) = self.nodes[self.current].parent {
-            if let Some(ref parent_state) = self.nodes[parent_id].state {
-                if let Ok(diff) = parent_state.difference(new_state) {
-                    return match color {
-                        Color::Black => diff.captures.white == 0 && diff.captures.black == 1,
-                        Color::White => diff.captures.black == 0 && diff.captures.white == 1,
+    /// Places the standard handicap stones for the board size on the root node, and sets White to
+    /// play first
+    pub fn place_fixed_handicap(&mut self, stones: u32) -> Result<Vec<Position>, BadukError> {
+        let (width, height) = match self.nodes[self.root].state {
+            None => return Err(BadukErrorKind::MissingGoBoard.into()),
+            Some(ref state) => state.dimensions(),
+        };
+        let positions = fixed_handicap_positions(width, height, stones)?;
+        self.place_handicap(positions)
+    }
+
+    /// Places handicap stones at the given positions on the root node, and sets White to play first
+    pub fn place_free_handicap(&mut self, positions: &[Position]) -> Result<Vec<Position>, BadukError> {
+        self.place_handicap(positions.to_vec())
+    }
+
+    fn place_handicap(&mut self, positions: Vec<Position>) -> Result<Vec<Position>, BadukError> {
+        let root = self.root;
+        match self.nodes[root].state {
+            None => return Err(BadukErrorKind::MissingGoBoard.into()),
+            Some(ref state) => {
+                if !state.is_empty() || !self.nodes[root].children.is_empty() {
+                    return Err(BadukErrorKind::BoardNotEmpty.into());
+                }
+                let (width, height) = state.dimensions();
+                let stones = positions.len() as u32;
+                if stones < 2 || stones >= width * height {
+                    return Err(BadukErrorKind::InvalidHandicap(stones).into());
+                }
+                for (i, pos) in positions.iter().enumerate() {
+                    if !state.is_valid_position(*pos) {
+                        return Err(BadukErrorKind::InvalidPosition(*pos).into());
+                    }
+                    if positions[..i].contains(pos) {
+                        return Err(BadukErrorKind::AlreadyOccupied(*pos).into());
                     }
                 }
             }
         }
-        false
+        self.nodes[root].tokens.push(SgfToken::Handicap(positions.len() as u32));
+        for pos in &positions {
+            self.add_stone_on_node(*pos, Color::Black, root)?;
+        }
+        self.nodes[root].tokens.push(SgfToken::Unknown(("PL".to_string(), "W".to_string())));
+        Ok(positions)
+    }
+
+    /// Gets the color to play at the current node, based on the last move played or an explicit
+    /// `PL` token
+    pub fn next_to_play(&self) -> Color {
+        let mut node = Some(self.current);
+        while let Some(index) = node {
+            for token in self.nodes[index].tokens.iter().rev() {
+                match token {
+                    SgfToken::Move { color, .. } => return !*color,
+                    SgfToken::Unknown((ident, value)) if ident == "PL" => match value.as_str() {
+                        "B" => return Color::Black,
+                        "W" => return Color::White,
+                        _ => {}
+                    },
+                    _ => {}
+                }
+            }
+            node = self.nodes[index].parent;
+        }
+        Color::Black
     }
 
     pub fn add_token(&mut self, node: GameTreeIndex, token: &SgfToken) -> GameTreeIndex {
@@ -192,23 +523,62 @@ impl GameTree {
         node
     }
 
+    pub(crate) fn replace_token(&mut self, node: GameTreeIndex, matches: impl Fn(&SgfToken) -> bool, token: Option<SgfToken>) {
+        self.nodes[node].replace_token(matches, token);
+    }
+
     pub fn set_size(&mut self, width: u32, height: u32, node: GameTreeIndex) -> Result<GameTreeIndex, BadukError> {
         if node != self.root {
             Err(BadukErrorKind::InvalidRootNode.into())
         } else {
+            // Setup given before the size is placed again on the new board
+            let state = replay_tokens(GameState::new(width, height), &self.nodes[node].tokens);
             self.nodes[node].tokens.push(SgfToken::Size(width, height));
-            self.nodes[node].state = Some(GameState::new(width, height));
+            self.nodes[node].state = Some(state);
             Ok(node)
         }
     }
 
+    fn is_old_style_pass(&self, pos: (u8, u8), node: GameTreeIndex) -> bool {
+        match self.stored_state(node) {
+            Some(state) => is_old_style_pass(state, pos),
+            None => false,
+        }
+    }
+
+    pub fn to_sgf(&self) -> String {
+        sgf::write(self)
+    }
+
     pub fn parse_sgf_token(&mut self, token: &SgfToken, node: GameTreeIndex) -> Result<GameTreeIndex, BadukError> {
         let index = match token {
-            SgfToken::Move{color, coordinate} => {
-                self.play_move_on_node(*coordinate, *color, node)?
+            SgfToken::Move{color, action: Action::Move(x, y)} if self.is_old_style_pass((*x, *y), node) => {
+                self.pass_on_node(token, *color, node)?
+            },
+            SgfToken::Move{color, action: Action::Pass} => {
+                self.pass_on_node(token, *color, node)?
+            },
+            SgfToken::Move{color, action: Action::Move(x, y)} => {
+                self.play_move_on_node((*x, *y), *color, node)?
             },
             SgfToken::Add{color, coordinate} => {
-                self.add_stone_on_node(*coordinate, *color, node)?
+                self.apply_setup((*coordinate).into(), Some(*color), node)?;
+                self.add_token(node, token)
+            },
+            SgfToken::Unknown((ident, _)) if matches!(ident.as_str(), "AB" | "AW" | "AE") => {
+                let setup = setup_from_token(token);
+                if setup.is_empty() {
+                    return Err(BadukErrorKind::InvalidInput.into());
+                }
+                let mut state = match self.state(node) {
+                    None => return Err(BadukErrorKind::MissingGoBoard.into()),
+                    Some(state) => state.into_owned(),
+                };
+                for (pos, color) in setup {
+                    state = apply_setup_to_state(&state, pos, color)?;
+                }
+                self.nodes[node].state = Some(state);
+                self.add_token(node, token)
             },
             SgfToken::Size(width, height) => {
                 let _ = self.set_size(*width, *height, node)?;
@@ -231,29 +601,134 @@ impl From<&SgfTree> for GameTree {
     }
 }
 
+impl TryFrom<&[u8]> for GameTree {
+    type Error = BadukError;
+
+    fn try_from(input: &[u8]) -> Result<GameTree, BadukError> {
+        sgf::parse_bytes(input)
+    }
+}
+
 impl TryFrom<&str> for GameTree {
     type Error = BadukError;
 
     fn try_from(input: &str) -> Result<GameTree, BadukError> {
-        let tree: SgfTree = parse(input).map_err(BadukError::invalid_root_node)?;
-        Ok((&tree).into())
+        sgf::parse(input)
+    }
+}
+
+/// Gets the points set up by a token, with the color put there or `None` for cleared points.
+/// Compressed point lists, like `AB[aa:cc]`, are kept as a single unknown token.
+fn setup_from_token(token: &SgfToken) -> Vec<(Position, Option<Color>)> {
+    let (color, value) = match token {
+        SgfToken::Add { color, coordinate } => return vec![((*coordinate).into(), Some(*color))],
+        SgfToken::Unknown((ident, value)) => match ident.as_str() {
+            "AB" => (Some(Color::Black), value),
+            "AW" => (Some(Color::White), value),
+            "AE" => (None, value),
+            _ => return vec![],
+        },
+        _ => return vec![],
+    };
+    sgf::parse_point_list(value)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|point| (point.into(), color))
+        .collect()
+}
+
+fn setup_to_token(pos: Position, color: Option<Color>) -> SgfToken {
+    match color {
+        Some(color) => SgfToken::Add { coordinate: pos.into(), color },
+        None => SgfToken::Unknown(("AE".to_string(), sgf::point_to_string(pos).unwrap_or_default())),
     }
 }
 
+fn apply_setup_to_state(state: &GameState, pos: Position, color: Option<Color>) -> Result<GameState, BadukError> {
+    let mut state = match color {
+        Some(color) => state.add_stone(pos, color)?,
+        None => {
+            let mut state = state.clone();
+            state.remove_stone(pos)?;
+            state
+        }
+    };
+    state.ko = None;
+    Ok(state)
+}
+
+/// Gets the board after a move, failing for moves that are suicidal or directly retake a ko
+fn play_on_state(state: &GameState, pos: Position, color: Color) -> Result<GameState, BadukError> {
+    let mut new_state = state.place_stone(pos, color)?;
+    let removed = new_state.remove_dead_stones(!color);
+    if !new_state.is_valid() {
+        return Err(BadukErrorKind::SuicidalMove.into());
+    }
+    if state.ko == Some(pos) && state.to_move == Some(color) {
+        return Err(BadukErrorKind::RetakingKo.into());
+    }
+    new_state.capture_stones(removed.len() as i32, !color);
+    new_state.record_move(pos, color, &removed);
+    Ok(new_state)
+}
+
+/// Gets the board after a pass, which ends any ko restriction
+fn pass_on_state(state: &GameState, color: Color) -> GameState {
+    let mut state = state.clone();
+    state.to_move = Some(!color);
+    state.ko = None;
+    state
+}
+
+/// Older SGF versions use `tt` as a pass on boards up to 19x19. The token is kept as written, so
+/// it is preserved when writing the game back out
+fn is_old_style_pass(state: &GameState, pos: (u8, u8)) -> bool {
+    pos == (20, 20) && state.width <= 19 && state.height <= 19
+}
+
+/// Applies the setup and moves of a node to the state of its parent. Tokens that can not be
+/// applied are skipped, the same way they are when parsing.
+fn replay_tokens(mut state: GameState, tokens: &[SgfToken]) -> GameState {
+    for token in tokens {
+        let new_state = match token {
+            SgfToken::Move { color, action: Action::Move(x, y) } if is_old_style_pass(&state, (*x, *y)) => {
+                Ok(pass_on_state(&state, *color))
+            }
+            SgfToken::Move { color, action: Action::Move(x, y) } => play_on_state(&state, (*x, *y).into(), *color),
+            SgfToken::Move { color, action: Action::Pass } => Ok(pass_on_state(&state, *color)),
+            _ => {
+                let setup = setup_from_token(token);
+                if setup.is_empty() {
+                    continue;
+                }
+                setup
+                    .into_iter()
+                    .try_fold(state.clone(), |state, (pos, color)| apply_setup_to_state(&state, pos, color))
+            }
+        };
+        if let Ok(new_state) = new_state {
+            state = new_state;
+        }
+    }
+    state
+}
+
 fn parse_variation(game: &mut GameTree, tree: &SgfTree, mut current: Option<GameTreeIndex>) {
     tree.nodes.iter().for_each(|node| {
         current = match current {
             None => Some(game.current),
             Some(node) => Some(game.create_new_node(node))
         };
+        let index = current.expect("previous statement guarentees no-None value");
         node.tokens.iter().for_each(|token| {
-            if let Err(e) = game.parse_sgf_token(token, current.expect("previous statement guarentees no-None value")) {
-                println!("Error parsing sgf token: {:?}, {:?}", token, e);
+            // Tokens that can not be applied to the board are kept as they are
+            if game.parse_sgf_token(token, index).is_err() {
+                game.add_token(index, token);
             }
         });
     });
     tree.variations.iter().for_each(|variation| {
-        parse_variation(game, &variation, current);
+        parse_variation(game, variation, current);
     });
 }
 
@@ -267,6 +742,12 @@ impl From<GameState> for GameTree {
                 }
             }
         }
+        if let Some(ref mut root) = game.nodes[game.root].state {
+            root.captures = state.captures;
+            root.to_move = state.to_move;
+            root.ko = state.ko;
+            root.komi = state.komi;
+        }
         game
     }
 }
@@ -0,0 +1,221 @@
+//! Reading and writing Tygem GIB files.
+//!
+//! The header holds lines like `\[GAMEBLACKNAME=Lee Sedol (9D)\]`, and the game lines like
+//! `STO 0 2 1 15 3` for move 2 by black (1) or white (2) at the 0-based point 15,3 from the top
+//! left. Komi and the result are read from `GAMEINFOMAIN`. Boards are always 19x19, so only
+//! 19x19 games can be written.
+
+use crate::{GameTree, BadukError, BadukErrorKind, GameResult, Color, Position};
+use crate::record::{self, Record};
+
+/// Parses a GIB file into a `GameTree`
+pub fn parse(input: &str) -> Result<GameTree, BadukError> {
+    let mut record = Record::new(19, 19);
+    let mut found = false;
+    let mut in_game = false;
+    for line in input.lines().map(str::trim) {
+        match line {
+            "\\HS" => found = true,
+            "\\GS" => {
+                found = true;
+                in_game = true;
+            }
+            "\\GE" => in_game = false,
+            _ if in_game => parse_game_line(&mut record, line)?,
+            _ => {
+                if let Some((key, value)) = header_value(line) {
+                    parse_header(&mut record, key, value);
+                }
+            }
+        }
+    }
+    if !found {
+        return Err(BadukErrorKind::InvalidInput.into());
+    }
+    record.into_game_tree()
+}
+
+/// Parses a GIB file from raw bytes. Data that is not valid UTF-8 is read as EUC-KR.
+pub fn parse_bytes(input: &[u8]) -> Result<GameTree, BadukError> {
+    parse(&record::decode(input, encoding_rs::EUC_KR))
+}
+
+/// Writes the main line of a `GameTree` as a GIB file, failing for boards other than 19x19
+pub fn write(game: &GameTree) -> Result<String, BadukError> {
+    let record = Record::from_game_tree(game);
+    record.check_size(19..=19)?;
+    let mut out = String::from("\\HS\n");
+    let player = |name: &Option<String>, rank: &Option<String>| match (name, rank) {
+        (name, Some(rank)) => format!("{} ({})", name.as_deref().unwrap_or_default(), rank),
+        (name, None) => name.clone().unwrap_or_default(),
+    };
+    let mut header = vec![
+        ("GAMEBLACKNAME", player(&record.black_player, &record.black_rank)),
+        ("GAMEWHITENAME", player(&record.white_player, &record.white_rank)),
+    ];
+    if let Some(ref name) = record.name {
+        header.push(("GAMENAME", name.clone()));
+    }
+    if let Some(ref date) = record.date {
+        header.push(("GAMEDATE", date.clone()));
+    }
+    if let Some(ref place) = record.place {
+        header.push(("GAMEPLACE", place.clone()));
+    }
+    let mut info = vec![];
+    if let Some(result) = record.result {
+        let code = match result {
+            GameResult::WinByScore(color, _) | GameResult::Win(color) => Some(by_color(color, 0, 1)),
+            GameResult::WinByResignation(color) => Some(by_color(color, 3, 4)),
+            GameResult::WinByTime(color) => Some(by_color(color, 7, 8)),
+            _ => None,
+        };
+        if let Some(code) = code {
+            info.push(format!("GRLT:{}", code));
+            info.push(format!("ZIPSU:{}", (result.score().unwrap_or(0.0) * 10.0).round()));
+        }
+    }
+    if let Some(komi) = record.komi {
+        info.push(format!("GONGJE:{}", (komi * 10.0).round()));
+    }
+    if !info.is_empty() {
+        header.push(("GAMEINFOMAIN", info.join(",")));
+    }
+    for (key, value) in header {
+        out.push_str(&format!("\\[{}={}\\]\n", key, value));
+    }
+    out.push_str("\\HE\n\\GS\n");
+    out.push_str(&format!("INI 0 1 {} &4\n", record.handicap));
+    for (number, (color, pos)) in (1..).zip(&record.moves) {
+        match pos {
+            Some(pos) => out.push_str(&format!(
+                "STO 0 {} {} {} {}\n",
+                number,
+                by_color(*color, 1, 2),
+                pos.x() - 1,
+                pos.y() - 1
+            )),
+            None => out.push_str(&format!("SKI 0 {}\n", number)),
+        }
+    }
+    out.push_str("\\GE\n");
+    Ok(out)
+}
+
+/// Gets the key and value of a header line like `\[KEY=VALUE\]`
+fn header_value(line: &str) -> Option<(&str, &str)> {
+    let line = line.strip_prefix("\\[")?.strip_suffix("\\]")?;
+    line.split_once('=')
+}
+
+fn parse_header(record: &mut Record, key: &str, value: &str) {
+    let value = value.trim();
+    match key {
+        "GAMEBLACKNAME" | "GAMEWHITENAME" if !value.is_empty() => {
+            let (name, rank) = split_player(value);
+            if key == "GAMEBLACKNAME" {
+                record.black_player = Some(name);
+                record.black_rank = rank;
+            } else {
+                record.white_player = Some(name);
+                record.white_rank = rank;
+            }
+        }
+        "GAMENAME" if !value.is_empty() => record.name = Some(value.to_string()),
+        "GAMEPLACE" if !value.is_empty() => record.place = Some(value.to_string()),
+        "GAMEDATE" => record.date = parse_date(value),
+        "GAMEINFOMAIN" => {
+            let field = |name: &str| {
+                value
+                    .split(',')
+                    .filter_map(|field| field.split_once(':'))
+                    .find(|(key, _)| *key == name)
+                    .and_then(|(_, value)| value.trim().parse::<f32>().ok())
+            };
+            if let Some(komi) = field("GONGJE") {
+                record.komi = Some(komi / 10.0);
+            }
+            let score = field("ZIPSU").unwrap_or(0.0) / 10.0;
+            record.result = match field("GRLT").map(|code| code as u32) {
+                Some(0) if score > 0.0 => Some(GameResult::WinByScore(Color::Black, score)),
+                Some(1) if score > 0.0 => Some(GameResult::WinByScore(Color::White, score)),
+                Some(0) => Some(GameResult::Win(Color::Black)),
+                Some(1) => Some(GameResult::Win(Color::White)),
+                Some(3) => Some(GameResult::WinByResignation(Color::Black)),
+                Some(4) => Some(GameResult::WinByResignation(Color::White)),
+                Some(7) => Some(GameResult::WinByTime(Color::Black)),
+                Some(8) => Some(GameResult::WinByTime(Color::White)),
+                _ => record.result,
+            };
+        }
+        _ => {}
+    }
+}
+
+fn parse_game_line(record: &mut Record, line: &str) -> Result<(), BadukError> {
+    let fields = line.split_whitespace().collect::<Vec<_>>();
+    let number = |index: usize| {
+        fields
+            .get(index)
+            .and_then(|field| field.parse::<u32>().ok())
+            .ok_or_else(|| BadukError::from(BadukErrorKind::InvalidInput))
+    };
+    match fields.first() {
+        Some(&"INI") => record.handicap = number(3)?,
+        Some(&"STO") => {
+            let color = match number(3)? {
+                1 => Color::Black,
+                2 => Color::White,
+                _ => return Err(BadukErrorKind::InvalidInput.into()),
+            };
+            let (x, y) = (number(4)?, number(5)?);
+            if x >= record.width || y >= record.height {
+                return Err(BadukErrorKind::InvalidInput.into());
+            }
+            record.moves.push((color, Some(Position::from((x + 1, y + 1)))));
+        }
+        Some(&"SKI") => {
+            let color = match record.moves.last() {
+                Some((color, _)) => !*color,
+                None if record.handicap >= 2 => Color::White,
+                None => Color::Black,
+            };
+            record.moves.push((color, None));
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Splits a player like `Lee Sedol (9D)` into the name and the rank
+fn split_player(value: &str) -> (String, Option<String>) {
+    if let Some(start) = value.rfind('(') {
+        if value.ends_with(')') {
+            let rank = value[start + 1..value.len() - 1].trim();
+            return (value[..start].trim().to_string(), Some(rank.to_string()).filter(|rank| !rank.is_empty()));
+        }
+    }
+    (value.to_string(), None)
+}
+
+/// Reads dates like `2016- 3- 9-13-05-22` as `2016-03-09`
+fn parse_date(value: &str) -> Option<String> {
+    let parts = value
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|part| !part.is_empty())
+        .take(3)
+        .map(|part| part.parse::<u32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    match parts.as_slice() {
+        [year, month, day] => Some(format!("{:04}-{:02}-{:02}", year, month, day)),
+        _ if !value.is_empty() => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+fn by_color(color: Color, black: u32, white: u32) -> u32 {
+    match color {
+        Color::Black => black,
+        Color::White => white,
+    }
+}
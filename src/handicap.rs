@@ -0,0 +1,75 @@
+use crate::{BadukError, BadukErrorKind, Position};
+
+/// Gets the handicap stone positions for a board, using the same placement as the GTP
+/// `fixed_handicap` command.
+///
+/// Supports 2 to 9 stones on odd sized boards from 9x9 and up, and 2 to 4 stones on other boards
+/// from 7x7 and up.
+pub fn fixed_handicap_positions(width: u32, height: u32, stones: u32) -> Result<Vec<Position>, BadukError> {
+    if width < 7 || height < 7 || stones < 2 || stones > max_fixed_handicap(width, height) {
+        return Err(BadukErrorKind::InvalidHandicap(stones).into());
+    }
+    let (left, center_x, right) = star_lines(width);
+    let (top, center_y, bottom) = star_lines(height);
+
+    // Positions are stored with y = 1 at the top, so GTP's D4 is the bottom left star point
+    let lower_left = (left, bottom);
+    let upper_right = (right, top);
+    let upper_left = (left, top);
+    let lower_right = (right, bottom);
+    let left_side = (left, center_y);
+    let right_side = (right, center_y);
+    let bottom_side = (center_x, bottom);
+    let top_side = (center_x, top);
+    let center = (center_x, center_y);
+
+    let points = match stones {
+        2 => vec![lower_left, upper_right],
+        3 => vec![lower_left, upper_right, upper_left],
+        4 => vec![lower_left, upper_right, upper_left, lower_right],
+        5 => vec![lower_left, upper_right, upper_left, lower_right, center],
+        6 => vec![lower_left, upper_right, upper_left, lower_right, left_side, right_side],
+        7 => vec![lower_left, upper_right, upper_left, lower_right, left_side, right_side, center],
+        8 => vec![lower_left, upper_right, upper_left, lower_right, left_side, right_side, bottom_side, top_side],
+        _ => vec![lower_left, upper_right, upper_left, lower_right, left_side, right_side, bottom_side, top_side, center],
+    };
+    Ok(points.into_iter().map(Position::from).collect())
+}
+
+/// Largest number of stones `fixed_handicap_positions` can place on a board of the given size
+pub fn max_fixed_handicap(width: u32, height: u32) -> u32 {
+    if width < 7 || height < 7 {
+        0
+    } else if width >= 9 && height >= 9 && width % 2 == 1 && height % 2 == 1 {
+        9
+    } else {
+        4
+    }
+}
+
+fn star_lines(size: u32) -> (u32, u32, u32) {
+    let edge = if size >= 13 { 4 } else { 3 };
+    (edge, size / 2 + 1, size + 1 - edge)
+}
+
+/// Gets the star points, or hoshi, usually marked on a board of the given size, row by row from the
+/// top left
+pub fn star_points(width: u32, height: u32) -> Vec<Position> {
+    if width < 7 || height < 7 {
+        return vec![];
+    }
+    let (left, center_x, right) = star_lines(width);
+    let (top, center_y, bottom) = star_lines(height);
+    let is_odd = width % 2 == 1 && height % 2 == 1;
+    let has_sides = is_odd && width >= 15 && height >= 15;
+
+    let mut points = vec![(left, top), (right, top), (left, bottom), (right, bottom)];
+    if has_sides {
+        points.extend(vec![(center_x, top), (left, center_y), (right, center_y), (center_x, bottom)]);
+    }
+    if is_odd {
+        points.push((center_x, center_y));
+    }
+    points.sort_by_key(|(x, y)| (*y, *x));
+    points.into_iter().map(Position::from).collect()
+}
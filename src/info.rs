@@ -0,0 +1,274 @@
+use crate::{GameTree, BadukError, BadukErrorKind, Color, SgfToken};
+use sgf_parser::{Outcome, RuleSet};
+use std::fmt;
+
+/// The result of a game, as described by the SGF `RE` property
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameResult {
+    WinByScore(Color, f32),
+    WinByResignation(Color),
+    WinByTime(Color),
+    WinByForfeit(Color),
+    /// Win without a known reason or score, `B+` or `W+`
+    Win(Color),
+    /// Jigo, `0`
+    Draw,
+    /// No result, or suspended play
+    Void,
+    /// The result is unknown, `?`
+    Unknown,
+}
+
+impl GameResult {
+    pub fn winner(&self) -> Option<Color> {
+        match self {
+            GameResult::WinByScore(color, _)
+            | GameResult::WinByResignation(color)
+            | GameResult::WinByTime(color)
+            | GameResult::WinByForfeit(color)
+            | GameResult::Win(color) => Some(*color),
+            _ => None,
+        }
+    }
+
+    /// Checks if the result ends the game, as a win or a draw. A void or unknown result does not.
+    pub fn is_decisive(&self) -> bool {
+        self.winner().is_some() || *self == GameResult::Draw
+    }
+
+    /// Gets the winning margin of a win by score
+    pub fn score(&self) -> Option<f32> {
+        match self {
+            GameResult::WinByScore(_, score) => Some(*score),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn from_outcome(outcome: &Outcome) -> GameResult {
+        match outcome {
+            Outcome::WinnerByPoints(color, score) => GameResult::WinByScore(*color, *score),
+            Outcome::WinnerByResign(color) => GameResult::WinByResignation(*color),
+            Outcome::WinnerByTime(color) => GameResult::WinByTime(*color),
+            Outcome::WinnerByForfeit(color) => GameResult::WinByForfeit(*color),
+            Outcome::Draw => GameResult::Draw,
+        }
+    }
+
+    pub(crate) fn to_token(self) -> SgfToken {
+        let outcome = match self {
+            GameResult::WinByScore(color, score) => Outcome::WinnerByPoints(color, score),
+            GameResult::WinByResignation(color) => Outcome::WinnerByResign(color),
+            GameResult::WinByTime(color) => Outcome::WinnerByTime(color),
+            GameResult::WinByForfeit(color) => Outcome::WinnerByForfeit(color),
+            GameResult::Draw => Outcome::Draw,
+            _ => return SgfToken::Unknown(("RE".to_string(), self.to_string())),
+        };
+        SgfToken::Result(outcome)
+    }
+}
+
+impl std::str::FromStr for GameResult {
+    type Err = BadukError;
+
+    fn from_str(value: &str) -> Result<GameResult, BadukError> {
+        let value = value.trim();
+        match value {
+            "0" | "Draw" | "D" | "Jigo" => return Ok(GameResult::Draw),
+            "Void" => return Ok(GameResult::Void),
+            "?" => return Ok(GameResult::Unknown),
+            _ => {}
+        }
+        let mut parts = value.splitn(2, '+');
+        let color = match parts.next() {
+            Some("B") => Color::Black,
+            Some("W") => Color::White,
+            _ => return Err(BadukErrorKind::InvalidInput.into()),
+        };
+        match parts.next() {
+            Some("") => Ok(GameResult::Win(color)),
+            Some("R") | Some("Resign") => Ok(GameResult::WinByResignation(color)),
+            Some("T") | Some("Time") => Ok(GameResult::WinByTime(color)),
+            Some("F") | Some("Forfeit") => Ok(GameResult::WinByForfeit(color)),
+            Some(score) => score
+                .parse()
+                .map(|score| GameResult::WinByScore(color, score))
+                .map_err(BadukError::invalid_input),
+            None => Err(BadukErrorKind::InvalidInput.into()),
+        }
+    }
+}
+
+impl fmt::Display for GameResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let winner = |color: &Color| match color {
+            Color::Black => "B",
+            Color::White => "W",
+        };
+        match self {
+            GameResult::WinByScore(color, score) => write!(f, "{}+{}", winner(color), score),
+            GameResult::WinByResignation(color) => write!(f, "{}+R", winner(color)),
+            GameResult::WinByTime(color) => write!(f, "{}+T", winner(color)),
+            GameResult::WinByForfeit(color) => write!(f, "{}+F", winner(color)),
+            GameResult::Win(color) => write!(f, "{}+", winner(color)),
+            GameResult::Draw => write!(f, "0"),
+            GameResult::Void => write!(f, "Void"),
+            GameResult::Unknown => write!(f, "?"),
+        }
+    }
+}
+
+/// Typed view of the game information stored in the root node
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GameInfo {
+    pub black_player: Option<String>,
+    pub white_player: Option<String>,
+    pub black_rank: Option<String>,
+    pub white_rank: Option<String>,
+    pub komi: Option<f32>,
+    pub result: Option<GameResult>,
+    pub date: Option<String>,
+    pub rules: Option<RuleSet>,
+    pub event: Option<String>,
+}
+
+impl GameTree {
+    /// Reads all game information from the root node
+    pub fn game_info(&self) -> GameInfo {
+        GameInfo {
+            black_player: self.player_name(Color::Black),
+            white_player: self.player_name(Color::White),
+            black_rank: self.player_rank(Color::Black),
+            white_rank: self.player_rank(Color::White),
+            komi: self.komi(),
+            result: self.result(),
+            date: self.date(),
+            rules: self.rules(),
+            event: self.event(),
+        }
+    }
+
+    /// Writes all game information to the root node, removing tokens for fields set to `None`
+    pub fn set_game_info(&mut self, info: &GameInfo) {
+        self.set_player_name(Color::Black, info.black_player.as_deref());
+        self.set_player_name(Color::White, info.white_player.as_deref());
+        self.set_player_rank(Color::Black, info.black_rank.as_deref());
+        self.set_player_rank(Color::White, info.white_rank.as_deref());
+        self.set_komi(info.komi);
+        self.set_result(info.result);
+        self.set_date(info.date.as_deref());
+        self.set_rules(info.rules.clone());
+        self.set_event(info.event.as_deref());
+    }
+
+    pub fn player_name(&self, color: Color) -> Option<String> {
+        self.find_root_token(|token| match token {
+            SgfToken::PlayerName { color: c, name } if *c == color => Some(name.clone()),
+            _ => None,
+        })
+    }
+
+    pub fn set_player_name(&mut self, color: Color, name: Option<&str>) {
+        self.replace_root_token(
+            |token| matches!(token, SgfToken::PlayerName { color: c, .. } if *c == color),
+            name.map(|name| SgfToken::PlayerName { color, name: name.to_string() }),
+        );
+    }
+
+    pub fn player_rank(&self, color: Color) -> Option<String> {
+        self.find_root_token(|token| match token {
+            SgfToken::PlayerRank { color: c, rank } if *c == color => Some(rank.clone()),
+            _ => None,
+        })
+    }
+
+    pub fn set_player_rank(&mut self, color: Color, rank: Option<&str>) {
+        self.replace_root_token(
+            |token| matches!(token, SgfToken::PlayerRank { color: c, .. } if *c == color),
+            rank.map(|rank| SgfToken::PlayerRank { color, rank: rank.to_string() }),
+        );
+    }
+
+    pub fn komi(&self) -> Option<f32> {
+        self.find_root_token(|token| match token {
+            SgfToken::Komi(komi) => Some(*komi),
+            SgfToken::Unknown((ident, value)) | SgfToken::Invalid((ident, value)) if ident == "KM" => {
+                value.trim().parse().ok()
+            }
+            _ => None,
+        })
+    }
+
+    pub fn set_komi(&mut self, komi: Option<f32>) {
+        self.replace_root_token(|token| is_property(token, "KM"), komi.map(SgfToken::Komi));
+    }
+
+    pub fn result(&self) -> Option<GameResult> {
+        self.find_root_token(|token| match token {
+            SgfToken::Result(outcome) => Some(GameResult::from_outcome(outcome)),
+            SgfToken::Unknown((ident, value)) | SgfToken::Invalid((ident, value)) if ident == "RE" => {
+                value.parse().ok()
+            }
+            _ => None,
+        })
+    }
+
+    pub fn set_result(&mut self, result: Option<GameResult>) {
+        self.replace_root_token(|token| is_property(token, "RE"), result.map(GameResult::to_token));
+    }
+
+    pub fn date(&self) -> Option<String> {
+        self.find_root_token(|token| match token {
+            SgfToken::Date(date) => Some(date.clone()),
+            _ => None,
+        })
+    }
+
+    pub fn set_date(&mut self, date: Option<&str>) {
+        self.replace_root_token(
+            |token| matches!(token, SgfToken::Date(_)),
+            date.map(|date| SgfToken::Date(date.to_string())),
+        );
+    }
+
+    pub fn rules(&self) -> Option<RuleSet> {
+        self.find_root_token(|token| match token {
+            SgfToken::Rule(rules) => Some(rules.clone()),
+            _ => None,
+        })
+    }
+
+    pub fn set_rules(&mut self, rules: Option<RuleSet>) {
+        self.replace_root_token(|token| matches!(token, SgfToken::Rule(_)), rules.map(SgfToken::Rule));
+    }
+
+    pub fn event(&self) -> Option<String> {
+        self.find_root_token(|token| match token {
+            SgfToken::Event(event) => Some(event.clone()),
+            _ => None,
+        })
+    }
+
+    pub fn set_event(&mut self, event: Option<&str>) {
+        self.replace_root_token(
+            |token| matches!(token, SgfToken::Event(_)),
+            event.map(|event| SgfToken::Event(event.to_string())),
+        );
+    }
+
+    fn find_root_token<T>(&self, f: impl Fn(&SgfToken) -> Option<T>) -> Option<T> {
+        self.nodes[self.root].tokens.iter().find_map(f)
+    }
+
+    fn replace_root_token(&mut self, matches: impl Fn(&SgfToken) -> bool, token: Option<SgfToken>) {
+        self.replace_token(self.root, matches, token);
+    }
+}
+
+/// Checks if a token is the given property, including unknown or invalid values for it
+fn is_property(token: &SgfToken, ident: &str) -> bool {
+    match (token, ident) {
+        (SgfToken::Komi(_), "KM") | (SgfToken::Result(_), "RE") => true,
+        (SgfToken::Unknown((id, _)), _) | (SgfToken::Invalid((id, _)), _) => id == ident,
+        _ => false,
+    }
+}
@@ -1,10 +1,42 @@
 mod error;
+mod bitboard;
 mod state;
 mod position;
 mod game;
+mod handicap;
+mod info;
+mod outcome;
+mod annotation;
+mod comment;
+mod markup;
+mod render;
+mod svg;
+mod diagram;
+mod play;
+mod clock;
+mod record;
+#[cfg(feature = "serde")]
+pub mod serialize;
+pub mod sgf;
+pub mod gib;
+pub mod ngf;
+pub mod ugf;
+#[cfg(feature = "json")]
+pub mod ogs;
 
-pub use crate::game::GameTree;
+pub use crate::game::{GameTree, GameTreeNode, GameTreeIndex};
 pub use crate::error::{BadukError, BadukErrorKind};
+pub use crate::bitboard::BitBoard;
 pub use crate::state::{GameState, GameStateDifference, Captures};
-pub use crate::position::Position;
-pub use sgf_parser::{Color, SgfToken};
+pub use crate::position::{Coordinates, Notation, Position};
+pub use crate::info::{GameInfo, GameResult};
+pub use crate::markup::Markup;
+pub use crate::annotation::{Emphasis, MoveAnnotation, PositionAnnotation};
+pub use crate::comment::{TextPattern, search_comments};
+pub use crate::handicap::{fixed_handicap_positions, max_fixed_handicap, star_points};
+pub use crate::render::{Orientation, TextRenderer};
+pub use crate::svg::SvgRenderer;
+pub use crate::diagram::{Diagram, DiagramNote};
+pub use crate::play::Game;
+pub use crate::clock::{Clock, SystemTimeSource, TimeControl, TimeLeft, TimeSource};
+pub use sgf_parser::{Color, RuleSet, SgfToken};
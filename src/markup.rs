@@ -0,0 +1,164 @@
+use crate::{GameTree, BadukError, BadukErrorKind, Position, SgfToken};
+use crate::game::GameTreeIndex;
+use crate::sgf::{parse_point, parse_point_list, point_to_string, split_composed, unescape_text};
+
+/// Board markup, as described by the SGF markup properties
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Markup {
+    /// `LB`
+    Label(Position, String),
+    /// `TR`
+    Triangle(Position),
+    /// `SQ`
+    Square(Position),
+    /// `CR`
+    Circle(Position),
+    /// `MA`
+    Cross(Position),
+    /// `AR`, from the first position to the second
+    Arrow(Position, Position),
+    /// `LN`
+    Line(Position, Position),
+    /// `DD`
+    Dimmed(Position),
+}
+
+impl Markup {
+    fn positions(&self) -> Vec<Position> {
+        match self {
+            Markup::Label(pos, _)
+            | Markup::Triangle(pos)
+            | Markup::Square(pos)
+            | Markup::Circle(pos)
+            | Markup::Cross(pos)
+            | Markup::Dimmed(pos) => vec![*pos],
+            Markup::Arrow(from, to) | Markup::Line(from, to) => vec![*from, *to],
+        }
+    }
+
+    /// Checks if two markups can not be on the same node at the same time. A point can only have
+    /// one label, and one shape among triangle, square, circle and cross.
+    fn conflicts_with(&self, other: &Markup) -> bool {
+        match (self, other) {
+            (Markup::Label(a, _), Markup::Label(b, _)) => a == b,
+            (Markup::Arrow(..), _) | (Markup::Line(..), _) | (Markup::Dimmed(_), _) => self == other,
+            (_, Markup::Label(..)) | (Markup::Label(..), _) => false,
+            (_, Markup::Arrow(..)) | (_, Markup::Line(..)) | (_, Markup::Dimmed(_)) => false,
+            _ => self.positions() == other.positions(),
+        }
+    }
+
+    fn to_token(&self) -> SgfToken {
+        let point = |pos: &Position| point_to_string(*pos).unwrap_or_default();
+        let unknown = |ident: &str, value: String| SgfToken::Unknown((ident.to_string(), value));
+        match self {
+            Markup::Label(pos, label) => SgfToken::Label {
+                label: label.clone(),
+                coordinate: (*pos).into(),
+            },
+            Markup::Triangle(pos) => SgfToken::Triangle { coordinate: (*pos).into() },
+            Markup::Square(pos) => SgfToken::Square { coordinate: (*pos).into() },
+            Markup::Circle(pos) => unknown("CR", point(pos)),
+            Markup::Cross(pos) => unknown("MA", point(pos)),
+            Markup::Arrow(from, to) => unknown("AR", format!("{}:{}", point(from), point(to))),
+            Markup::Line(from, to) => unknown("LN", format!("{}:{}", point(from), point(to))),
+            Markup::Dimmed(pos) => unknown("DD", point(pos)),
+        }
+    }
+
+    /// Gets the markup described by a token, or `None` if it is not a markup token
+    fn from_token(token: &SgfToken) -> Option<Vec<Markup>> {
+        let points = |value: &str| -> Vec<Position> {
+            parse_point_list(value)
+                .unwrap_or_default()
+                .into_iter()
+                .map(Position::from)
+                .collect()
+        };
+        let pair = |value: &str| -> Option<(Position, Position)> {
+            let (from, to) = split_composed(value)?;
+            Some((parse_point(from)?.into(), parse_point(to)?.into()))
+        };
+        let markup = match token {
+            SgfToken::Label { label, coordinate } => vec![Markup::Label((*coordinate).into(), label.clone())],
+            SgfToken::Triangle { coordinate } => vec![Markup::Triangle((*coordinate).into())],
+            SgfToken::Square { coordinate } => vec![Markup::Square((*coordinate).into())],
+            SgfToken::Unknown((ident, value)) => match ident.as_str() {
+                "CR" => points(value).into_iter().map(Markup::Circle).collect(),
+                "MA" => points(value).into_iter().map(Markup::Cross).collect(),
+                "DD" => points(value).into_iter().map(Markup::Dimmed).collect(),
+                "TR" => points(value).into_iter().map(Markup::Triangle).collect(),
+                "SQ" => points(value).into_iter().map(Markup::Square).collect(),
+                "AR" => pair(value).map(|(from, to)| Markup::Arrow(from, to)).into_iter().collect(),
+                "LN" => pair(value).map(|(from, to)| Markup::Line(from, to)).into_iter().collect(),
+                "LB" => split_composed(value)
+                    .and_then(|(point, label)| Some(Markup::Label(parse_point(point)?.into(), unescape_text(label))))
+                    .into_iter()
+                    .collect(),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(markup)
+    }
+}
+
+impl GameTree {
+    /// Gets all markup on a node, in the order it was added
+    pub fn get_markup(&self, node: GameTreeIndex) -> Vec<Markup> {
+        self.nodes[node]
+            .tokens
+            .iter()
+            .filter_map(Markup::from_token)
+            .flatten()
+            .collect()
+    }
+
+    /// Adds markup to a node, replacing any markup it can not coexist with, like a second label
+    /// on the same point
+    pub fn add_markup(&mut self, node: GameTreeIndex, markup: Markup) -> Result<GameTreeIndex, BadukError> {
+        let state = match self.stored_state(node) {
+            Some(state) => state,
+            None => return Err(BadukErrorKind::MissingGoBoard.into()),
+        };
+        if let Some(pos) = markup.positions().into_iter().find(|pos| !state.is_valid_position(*pos)) {
+            return Err(BadukErrorKind::InvalidPosition(pos).into());
+        }
+        if let Markup::Arrow(from, to) | Markup::Line(from, to) = markup {
+            if from == to {
+                return Err(BadukErrorKind::InvalidPosition(to).into());
+            }
+        }
+        self.retain_markup(node, |existing| !existing.conflicts_with(&markup));
+        self.nodes[node].tokens.push(markup.to_token());
+        Ok(node)
+    }
+
+    /// Removes markup from a node, returning false if the node did not have it
+    pub fn remove_markup(&mut self, node: GameTreeIndex, markup: &Markup) -> bool {
+        let before = self.get_markup(node).len();
+        self.retain_markup(node, |existing| existing != markup);
+        self.get_markup(node).len() != before
+    }
+
+    /// Removes all markup from a node
+    pub fn clear_markup(&mut self, node: GameTreeIndex) {
+        self.retain_markup(node, |_| false);
+    }
+
+    /// Keeps the markup matching the predicate. Tokens describing several points are split up
+    /// when only some of their points are removed.
+    fn retain_markup(&mut self, node: GameTreeIndex, keep: impl Fn(&Markup) -> bool) {
+        let tokens = std::mem::take(&mut self.nodes[node].tokens);
+        for token in tokens {
+            match Markup::from_token(&token) {
+                Some(markup) if markup.iter().all(&keep) => self.nodes[node].tokens.push(token),
+                Some(markup) => {
+                    let kept = markup.into_iter().filter(&keep).map(|markup| markup.to_token());
+                    self.nodes[node].tokens.extend(kept);
+                }
+                None => self.nodes[node].tokens.push(token),
+            }
+        }
+    }
+}
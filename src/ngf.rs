@@ -0,0 +1,171 @@
+//! Reading and writing WBaduk NGF files.
+//!
+//! The first twelve lines hold, in order: the game name, the board size, the white player, the
+//! black player, the website, the handicap, an unused field, the komi, the date, an unused field,
+//! the result and the number of moves. Each move follows on a line like `PMABBQDDQ`, with the move
+//! number, the color and the point, where `B` is the first line and anything outside the board
+//! is a pass.
+//!
+//! WBaduk leaves the half point out of the komi, so a whole number komi other than 0 is read with
+//! half a point added, and written without it.
+
+use crate::{GameTree, BadukError, BadukErrorKind, GameResult, Color, Position};
+use crate::record::{self, Record};
+
+/// Parses an NGF file into a `GameTree`
+pub fn parse(input: &str) -> Result<GameTree, BadukError> {
+    let lines = input.lines().map(str::trim).collect::<Vec<_>>();
+    if lines.len() < 12 {
+        return Err(BadukErrorKind::InvalidInput.into());
+    }
+    let size = lines[1].parse::<u32>().map_err(BadukError::invalid_input)?;
+    if size == 0 || size > 25 {
+        return Err(BadukErrorKind::InvalidInput.into());
+    }
+    let mut record = Record::new(size, size);
+    record.name = Some(lines[0].to_string()).filter(|name| !name.is_empty());
+    let (name, rank) = record::split_rank(lines[2]);
+    record.white_player = Some(name).filter(|name| !name.is_empty());
+    record.white_rank = rank;
+    let (name, rank) = record::split_rank(lines[3]);
+    record.black_player = Some(name).filter(|name| !name.is_empty());
+    record.black_rank = rank;
+    record.handicap = lines[5].parse().unwrap_or(0);
+    record.komi = lines[7].parse::<f32>().ok().map(|komi| {
+        if komi != 0.0 && komi.fract() == 0.0 {
+            komi + komi.signum() * 0.5
+        } else {
+            komi
+        }
+    });
+    record.date = parse_date(lines[8]);
+    record.result = parse_result(lines[10]);
+
+    for line in lines[12..].iter().filter(|line| line.starts_with("PM")) {
+        let bytes = line.as_bytes();
+        if bytes.len() < 7 {
+            return Err(BadukErrorKind::InvalidInput.into());
+        }
+        let color = match bytes[4] {
+            b'B' => Color::Black,
+            b'W' => Color::White,
+            _ => return Err(BadukErrorKind::InvalidInput.into()),
+        };
+        let coordinate = |c: u8| u32::from(c).checked_sub(u32::from(b'A')).filter(|c| (1..=size).contains(c));
+        let pos = coordinate(bytes[5]).zip(coordinate(bytes[6])).map(Position::from);
+        record.moves.push((color, pos));
+    }
+    record.into_game_tree()
+}
+
+/// Parses an NGF file from raw bytes. Data that is not valid UTF-8 is read as EUC-KR.
+pub fn parse_bytes(input: &[u8]) -> Result<GameTree, BadukError> {
+    parse(&record::decode(input, encoding_rs::EUC_KR))
+}
+
+/// Writes the main line of a `GameTree` as an NGF file, failing for boards that are not square or
+/// larger than 25x25
+pub fn write(game: &GameTree) -> Result<String, BadukError> {
+    let record = Record::from_game_tree(game);
+    record.check_size(1..=25)?;
+    let player = |name: &Option<String>, rank: &Option<String>| {
+        let name = name.clone().unwrap_or_default();
+        match rank {
+            Some(rank) => format!("{} {}", name, rank),
+            None => name,
+        }
+    };
+    let komi = record.komi.map(|komi| komi.trunc().to_string()).unwrap_or_else(|| "0".to_string());
+    let date = record
+        .date
+        .as_deref()
+        .map(|date| date.chars().filter(char::is_ascii_digit).take(8).collect::<String>())
+        .unwrap_or_default();
+    let result = match record.result {
+        Some(GameResult::WinByScore(color, score)) => format!("{} wins by {} points!", winner(color), score),
+        Some(GameResult::WinByResignation(color)) => format!("{} wins by resignation!", winner(color)),
+        Some(GameResult::WinByTime(color)) => format!("{} wins by time!", winner(color)),
+        Some(GameResult::WinByForfeit(color)) | Some(GameResult::Win(color)) => format!("{} wins!", winner(color)),
+        Some(GameResult::Draw) => "Draw!".to_string(),
+        _ => String::new(),
+    };
+    let mut lines = vec![
+        record.name.clone().unwrap_or_default(),
+        record.width.to_string(),
+        player(&record.white_player, &record.white_rank),
+        player(&record.black_player, &record.black_rank),
+        String::new(),
+        record.handicap.to_string(),
+        "0".to_string(),
+        komi,
+        date,
+        "0".to_string(),
+        result,
+        record.moves.len().to_string(),
+    ];
+    for (number, (color, pos)) in (1..).zip(&record.moves) {
+        let (x, y) = match pos {
+            Some(pos) => (coordinate(pos.x()), coordinate(pos.y())),
+            None => ('A', 'A'),
+        };
+        let color = match color {
+            Color::Black => 'B',
+            Color::White => 'W',
+        };
+        // The move number is not read back, so it wraps around after 675 moves
+        let (high, low) = ((number / 26) % 26, number % 26);
+        lines.push(format!("PM{}{}{}{}{}{}{}", coordinate(high), coordinate(low), color, x, y, y, x));
+    }
+    let mut out = lines.join("\n");
+    out.push('\n');
+    Ok(out)
+}
+
+/// Gets the letter for a value from 0 to 25, where `B` is the first line of the board
+fn coordinate(value: u32) -> char {
+    std::char::from_u32('A' as u32 + value).filter(char::is_ascii_uppercase).unwrap_or('A')
+}
+
+fn winner(color: Color) -> &'static str {
+    match color {
+        Color::Black => "Black",
+        Color::White => "White",
+    }
+}
+
+/// Reads dates like `20080425 [09:31]` as `2008-04-25`
+fn parse_date(value: &str) -> Option<String> {
+    let digits = value.chars().take_while(char::is_ascii_digit).collect::<String>();
+    if digits.len() == 8 {
+        Some(format!("{}-{}-{}", &digits[..4], &digits[4..6], &digits[6..]))
+    } else {
+        Some(value.to_string()).filter(|value| !value.is_empty())
+    }
+}
+
+/// Reads results like `White wins by resignation!` or `Black wins by 2.5 points!`
+fn parse_result(value: &str) -> Option<GameResult> {
+    let lower = value.to_lowercase();
+    let color = if lower.contains("white win") {
+        Color::White
+    } else if lower.contains("black win") {
+        Color::Black
+    } else if lower.contains("draw") || lower.contains("jigo") {
+        return Some(GameResult::Draw);
+    } else {
+        return None;
+    };
+    if lower.contains("resign") {
+        return Some(GameResult::WinByResignation(color));
+    }
+    if lower.contains("time") {
+        return Some(GameResult::WinByTime(color));
+    }
+    let score = lower
+        .split(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .find_map(|part| part.trim_end_matches('.').parse::<f32>().ok());
+    Some(match score {
+        Some(score) => GameResult::WinByScore(color, score),
+        None => GameResult::Win(color),
+    })
+}
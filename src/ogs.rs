@@ -0,0 +1,334 @@
+//! Reading and writing JSON game records in the shape used by the OGS API, enabled by the `json`
+//! feature.
+//!
+//! A record holds `width` and `height`, setup stones in `initial_state` as SGF points, like
+//! `{"black": "dddp", "white": ""}`, and `moves` as `[x, y, time]` with 0-based points from the
+//! top left, where `[-1, -1, time]` is a pass. Players have a `username` and a numeric `rank`, where
+//! 0 is 30 kyu and 30 is 1 dan. The `winner` is read as a player id or as `"black"` or `"white"`,
+//! and written as the color.
+//!
+//! Moves carry no color, so they alternate starting with `initial_player`. With free handicap
+//! placement the first `handicap` moves are Black's handicap stones. The time used for each move,
+//! in milliseconds, is kept as the private `MT` property on the move nodes.
+
+use crate::{GameTree, GameState, BadukError, BadukErrorKind, GameResult, Color, Position, SgfToken, fixed_handicap_positions};
+use crate::record::Record;
+use crate::sgf;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sgf_parser::RuleSet;
+
+/// Property holding the milliseconds used for a move
+const MOVE_TIME: &str = "MT";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct GameData {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    game_name: Option<String>,
+    width: u32,
+    height: u32,
+    #[serde(default)]
+    initial_state: InitialState,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    initial_player: Option<String>,
+    #[serde(default)]
+    moves: Vec<Vec<Value>>,
+    #[serde(default)]
+    players: Players,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    black_player_id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    white_player_id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    komi: Option<f32>,
+    #[serde(default)]
+    handicap: u32,
+    #[serde(default)]
+    free_handicap_placement: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rules: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    winner: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    outcome: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct InitialState {
+    #[serde(default)]
+    black: String,
+    #[serde(default)]
+    white: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Players {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    black: Option<Player>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    white: Option<Player>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Player {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<u64>,
+    #[serde(default)]
+    username: String,
+    #[serde(default, alias = "ranking", skip_serializing_if = "Option::is_none")]
+    rank: Option<f64>,
+}
+
+/// Parses a JSON game record into a `GameTree`
+pub fn parse(input: &str) -> Result<GameTree, BadukError> {
+    let data: GameData = serde_json::from_str(input).map_err(BadukError::invalid_input)?;
+    if !(1..=GameState::MAX_SIZE).contains(&data.width) || !(1..=GameState::MAX_SIZE).contains(&data.height) {
+        return Err(BadukErrorKind::InvalidInput.into());
+    }
+    let mut record = Record::new(data.width, data.height);
+    record.name = data.game_name.clone();
+    record.komi = data.komi;
+    record.handicap = data.handicap;
+    record.rules = data.rules.as_deref().map(parse_rules);
+    if let Some(ref player) = data.players.black {
+        record.black_player = Some(player.username.clone()).filter(|name| !name.is_empty());
+        record.black_rank = player.rank.map(rank_name);
+    }
+    if let Some(ref player) = data.players.white {
+        record.white_player = Some(player.username.clone()).filter(|name| !name.is_empty());
+        record.white_rank = player.rank.map(rank_name);
+    }
+    record.result = parse_result(&data);
+    for (color, points) in &[(Color::Black, &data.initial_state.black), (Color::White, &data.initial_state.white)] {
+        if points.len() % 2 != 0 {
+            return Err(BadukErrorKind::InvalidInput.into());
+        }
+        for index in (0..points.len()).step_by(2) {
+            let point = points.get(index..index + 2).and_then(sgf::parse_point);
+            let (x, y) = point.ok_or_else(|| BadukError::from(BadukErrorKind::InvalidInput))?;
+            record.setup.push((*color, Position::from((u32::from(x), u32::from(y)))));
+        }
+    }
+
+    let free_handicap = if data.free_handicap_placement && data.handicap >= 2 {
+        data.handicap as usize
+    } else {
+        0
+    };
+    let mut color = match data.initial_player.as_deref() {
+        _ if free_handicap > 0 => Color::White,
+        Some("white") => Color::White,
+        Some(_) => Color::Black,
+        None if data.handicap >= 2 => Color::White,
+        None => Color::Black,
+    };
+    let mut times = vec![];
+    for (index, entry) in data.moves.iter().enumerate() {
+        let coordinate = |index: usize| entry.get(index).and_then(Value::as_i64);
+        let pos = match (coordinate(0), coordinate(1)) {
+            (Some(-1), Some(-1)) => None,
+            (Some(x), Some(y)) if x >= 0 && y >= 0 && x < i64::from(data.width) && y < i64::from(data.height) => {
+                Some(Position::from((x as u32 + 1, y as u32 + 1)))
+            }
+            _ => return Err(BadukErrorKind::InvalidInput.into()),
+        };
+        if index < free_handicap {
+            match pos {
+                Some(pos) => record.setup.push((Color::Black, pos)),
+                None => return Err(BadukErrorKind::InvalidInput.into()),
+            }
+            continue;
+        }
+        record.moves.push((color, pos));
+        times.push(entry.get(2).filter(|time| time.is_number()).map(Value::to_string));
+        color = !color;
+    }
+
+    let mut game = record.into_game_tree()?;
+    let mut node = game.root;
+    for time in times {
+        node = game.nodes[node].children[0];
+        if let Some(time) = time {
+            game.add_token(node, &SgfToken::Unknown((MOVE_TIME.to_string(), time)));
+        }
+    }
+    Ok(game)
+}
+
+/// Writes the main line of a `GameTree` as a JSON game record
+pub fn write(game: &GameTree) -> String {
+    let record = Record::from_game_tree(game);
+    let mut data = GameData {
+        game_name: record.name.clone(),
+        width: record.width,
+        height: record.height,
+        komi: record.komi,
+        handicap: record.handicap,
+        rules: record.rules.as_ref().map(rules_name),
+        ..GameData::default()
+    };
+    let player = |name: &Option<String>, rank: &Option<String>| {
+        if name.is_none() && rank.is_none() {
+            return None;
+        }
+        Some(Player {
+            id: None,
+            username: name.clone().unwrap_or_default(),
+            rank: rank.as_deref().and_then(rank_value),
+        })
+    };
+    data.players.black = player(&record.black_player, &record.black_rank);
+    data.players.white = player(&record.white_player, &record.white_rank);
+    match record.result {
+        Some(GameResult::Draw) => data.outcome = Some("0 points".to_string()),
+        Some(result) => {
+            if let Some(color) = result.winner() {
+                data.winner = Some(Value::from(color_name(color)));
+                data.outcome = Some(match result {
+                    GameResult::WinByScore(_, score) => format!("{} points", score),
+                    GameResult::WinByResignation(_) => "Resignation".to_string(),
+                    GameResult::WinByTime(_) => "Timeout".to_string(),
+                    GameResult::WinByForfeit(_) => "Disqualification".to_string(),
+                    _ => String::new(),
+                });
+            }
+        }
+        None => {}
+    }
+
+    // Handicap stones away from the standard points are written as Black's first moves
+    let mut setup = record.setup.clone();
+    if record.handicap >= 2 {
+        let handicap_stones = setup
+            .iter()
+            .filter(|(color, _)| *color == Color::Black)
+            .map(|(_, pos)| *pos)
+            .collect::<Vec<_>>();
+        let mut fixed = fixed_handicap_positions(record.width, record.height, record.handicap).unwrap_or_default();
+        let mut stones = handicap_stones.clone();
+        fixed.sort_by_key(|pos| (pos.y(), pos.x()));
+        stones.sort_by_key(|pos| (pos.y(), pos.x()));
+        if stones != fixed {
+            data.free_handicap_placement = true;
+            data.moves.extend(handicap_stones.iter().map(|pos| move_entry(Some(*pos), Value::from(0))));
+        }
+        setup.retain(|(color, _)| *color == Color::White);
+    }
+    for (color, pos) in setup {
+        let point = sgf::point_to_string(pos).unwrap_or_default();
+        match color {
+            Color::Black => data.initial_state.black.push_str(&point),
+            Color::White => data.initial_state.white.push_str(&point),
+        }
+    }
+
+    let first = match record.moves.first() {
+        Some((color, _)) => *color,
+        None if record.handicap >= 2 => Color::White,
+        None => Color::Black,
+    };
+    data.initial_player = Some(color_name(first).to_string());
+    let mut times = vec![];
+    let mut node = Some(game.root);
+    while let Some(index) = node {
+        let tokens = &game.nodes[index].tokens;
+        if tokens.iter().any(|token| matches!(token, SgfToken::Move { .. })) {
+            let time = game.nodes[index]
+                .get_unknown_values(MOVE_TIME)
+                .first()
+                .and_then(|time| serde_json::from_str::<Value>(time).ok());
+            times.push(time.unwrap_or_else(|| Value::from(0)));
+        }
+        node = game.nodes[index].children.first().copied();
+    }
+    for ((_, pos), time) in record.moves.iter().zip(times) {
+        data.moves.push(move_entry(*pos, time));
+    }
+    serde_json::to_string(&data).expect("game records are always valid JSON")
+}
+
+fn move_entry(pos: Option<Position>, time: Value) -> Vec<Value> {
+    match pos {
+        Some(pos) => vec![Value::from(pos.x() - 1), Value::from(pos.y() - 1), time],
+        None => vec![Value::from(-1), Value::from(-1), time],
+    }
+}
+
+fn color_name(color: Color) -> &'static str {
+    match color {
+        Color::Black => "black",
+        Color::White => "white",
+    }
+}
+
+fn parse_rules(rules: &str) -> RuleSet {
+    match rules.to_lowercase().as_str() {
+        "japanese" => RuleSet::Japanese,
+        "chinese" => RuleSet::Chinese,
+        "aga" => RuleSet::AGA,
+        "nz" => RuleSet::NZ,
+        "ing" | "goe" => RuleSet::GOE,
+        _ => RuleSet::Unknown(rules.to_string()),
+    }
+}
+
+fn rules_name(rules: &RuleSet) -> String {
+    match rules {
+        RuleSet::GOE => "ing".to_string(),
+        rules => rules.to_string().to_lowercase(),
+    }
+}
+
+fn parse_result(data: &GameData) -> Option<GameResult> {
+    let outcome = data.outcome.as_deref().unwrap_or_default().trim().to_lowercase();
+    let score = outcome.trim_end_matches("points").trim().parse::<f32>().ok();
+    let winner = match data.winner.as_ref() {
+        Some(Value::String(color)) if color == "black" => Color::Black,
+        Some(Value::String(color)) if color == "white" => Color::White,
+        Some(Value::Number(id)) => {
+            let id = id.as_u64();
+            let player_id = |player: &Option<Player>, fallback: Option<u64>| {
+                player.as_ref().and_then(|player| player.id).or(fallback)
+            };
+            if id.is_some() && id == player_id(&data.players.black, data.black_player_id) {
+                Color::Black
+            } else if id.is_some() && id == player_id(&data.players.white, data.white_player_id) {
+                Color::White
+            } else {
+                return None;
+            }
+        }
+        _ if score == Some(0.0) => return Some(GameResult::Draw),
+        _ => return None,
+    };
+    Some(match outcome.as_str() {
+        "resignation" => GameResult::WinByResignation(winner),
+        "timeout" => GameResult::WinByTime(winner),
+        "disqualification" | "abandonment" => GameResult::WinByForfeit(winner),
+        _ => match score {
+            Some(score) => GameResult::WinByScore(winner, score),
+            None => GameResult::Win(winner),
+        },
+    })
+}
+
+/// Gets the name of a numeric rank, where 0 is 30 kyu and 30 is 1 dan
+fn rank_name(rank: f64) -> String {
+    let rank = rank.floor() as i64;
+    if rank < 30 {
+        format!("{}k", 30 - rank.max(0))
+    } else {
+        format!("{}d", rank - 29)
+    }
+}
+
+fn rank_value(rank: &str) -> Option<f64> {
+    let rank = rank.trim().to_lowercase();
+    let digits = rank.bytes().take_while(u8::is_ascii_digit).count();
+    let number = rank[..digits].parse::<f64>().ok()?;
+    match &rank[digits..] {
+        "k" => Some(30.0 - number),
+        "d" => Some(29.0 + number),
+        _ => None,
+    }
+}
@@ -0,0 +1,68 @@
+use crate::{GameTree, GameResult, BadukError, BadukErrorKind, Color};
+
+impl GameTree {
+    /// Checks if the game has ended with a win or a draw
+    pub fn is_over(&self) -> bool {
+        self.result().map(|result| result.is_decisive()).unwrap_or(false)
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Sets strict mode, where moves and passes can not be played once the game is over. Moves
+    /// read from a file are always kept.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Records the result of the game as `RE` on the root node. Fails if the game is already over.
+    pub fn end_game(&mut self, result: GameResult) -> Result<GameResult, BadukError> {
+        if self.is_over() {
+            return Err(BadukErrorKind::GameOver.into());
+        }
+        self.set_result(Some(result));
+        Ok(result)
+    }
+
+    /// Ends the game with a win by resignation for the other player
+    pub fn resign(&mut self, color: Color) -> Result<GameResult, BadukError> {
+        self.end_game(GameResult::WinByResignation(!color))
+    }
+
+    /// Ends the game with a win on time for the other player
+    pub fn time_out(&mut self, color: Color) -> Result<GameResult, BadukError> {
+        self.end_game(GameResult::WinByTime(!color))
+    }
+
+    /// Ends the game with a win by forfeit for the other player
+    pub fn forfeit(&mut self, color: Color) -> Result<GameResult, BadukError> {
+        self.end_game(GameResult::WinByForfeit(!color))
+    }
+
+    /// Ends the game by counting. Komi should already be included in the scores. Equal scores are
+    /// a jigo.
+    pub fn end_by_score(&mut self, black: f32, white: f32) -> Result<GameResult, BadukError> {
+        let result = if black > white {
+            GameResult::WinByScore(Color::Black, black - white)
+        } else if white > black {
+            GameResult::WinByScore(Color::White, white - black)
+        } else {
+            GameResult::Draw
+        };
+        self.end_game(result)
+    }
+
+    /// Ends the game without a result
+    pub fn void(&mut self) -> Result<GameResult, BadukError> {
+        self.end_game(GameResult::Void)
+    }
+
+    /// Fails in strict mode when the game is over
+    pub(crate) fn check_playable(&self) -> Result<(), BadukError> {
+        if self.strict && self.is_over() {
+            return Err(BadukErrorKind::GameOver.into());
+        }
+        Ok(())
+    }
+}
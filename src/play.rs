@@ -0,0 +1,160 @@
+use crate::{GameState, GameTree, GameResult, BadukError, BadukErrorKind, Color, Position};
+use crate::game::GameTreeIndex;
+use std::convert::TryFrom;
+
+/// Something that was undone and can be redone
+#[derive(Debug, Clone, Copy)]
+enum Undone {
+    Node(GameTreeIndex),
+    Result(GameResult),
+}
+
+/// A game played one move after another, for clients that do not need variations.
+///
+/// Moves are added to a `GameTree` in strict mode, which can be exported at any time. Undone moves
+/// are kept for `redo` until a new move is played. Then the ones played in this game are removed,
+/// while those that came with a tree the game was continued from stay as a variation.
+#[derive(Debug)]
+pub struct Game {
+    tree: GameTree,
+    undone: Vec<Undone>,
+    /// Number of nodes the tree had before this game added any
+    imported: usize,
+}
+
+impl Game {
+    pub fn new(width: u32, height: u32) -> Game {
+        let mut tree = GameTree::new(width, height);
+        tree.set_strict(true);
+        let imported = tree.count_nodes();
+        Game { tree, undone: vec![], imported }
+    }
+
+    /// Gets the board after the last move
+    pub fn state(&self) -> &GameState {
+        self.tree.current_state().expect("games always have a board")
+    }
+
+    /// Gets the color to play next
+    pub fn to_move(&self) -> Color {
+        self.tree.next_to_play()
+    }
+
+    pub fn result(&self) -> Option<GameResult> {
+        self.tree.result()
+    }
+
+    /// Checks if the game has ended with a win or a draw, after which no more moves can be played
+    pub fn is_over(&self) -> bool {
+        self.tree.is_over()
+    }
+
+    /// Gets the moves played so far, numbered from 1. Passes have no position.
+    pub fn moves(&self) -> Vec<(u32, Color, Option<Position>)> {
+        self.tree.numbered_moves(self.tree.current)
+    }
+
+    /// Plays a stone for the player to move, returning the stones it captured
+    pub fn play(&mut self, pos: impl Into<Position>) -> Result<Vec<Position>, BadukError> {
+        let color = self.to_move();
+        let before = self.state().stones(!color).clone();
+        self.tree.play_move_as_variation(pos, color, self.tree.current)?;
+        self.discard_undone();
+        let after = self.state();
+        Ok(before
+            .and_not(after.stones(!color))
+            .indices()
+            .map(|index| after.index_to_position(index))
+            .collect())
+    }
+
+    /// Passes for the player to move
+    pub fn pass(&mut self) -> Result<(), BadukError> {
+        self.tree.pass_as_variation(self.to_move(), self.tree.current)?;
+        self.discard_undone();
+        Ok(())
+    }
+
+    /// Ends the game with a win by resignation for the other player
+    pub fn resign(&mut self, color: Color) -> Result<(), BadukError> {
+        self.tree.resign(color)?;
+        self.undone.clear();
+        Ok(())
+    }
+
+    /// Takes back the result of the game, or else the last move, restoring the board, captures and
+    /// ko from before it. Returns false if there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        if let Some(result) = self.result() {
+            self.tree.set_result(None);
+            self.undone.push(Undone::Result(result));
+            return true;
+        }
+        match self.tree.nodes[self.tree.current].parent {
+            Some(parent) => {
+                self.undone.push(Undone::Node(self.tree.current));
+                self.tree.current = parent;
+                self.tree.cache_state(parent);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Plays again what was last undone. Returns false if there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        match self.undone.pop() {
+            Some(Undone::Node(node)) => {
+                self.tree.current = node;
+                self.tree.cache_state(node);
+                true
+            }
+            Some(Undone::Result(result)) => {
+                self.tree.set_result(Some(result));
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn tree(&self) -> &GameTree {
+        &self.tree
+    }
+
+    pub fn into_tree(self) -> GameTree {
+        self.tree
+    }
+
+    pub fn to_sgf(&self) -> String {
+        self.tree.to_sgf()
+    }
+
+    /// Removes the undone moves played in this game once a new move has been played in their
+    /// place. Nodes added by this game come after the imported ones, and so do all nodes below
+    /// them, so removing them leaves the indices of the imported nodes as they were.
+    fn discard_undone(&mut self) {
+        // The earliest undone move is the last one undone, and the later ones are below it
+        let imported = self.imported;
+        let earliest = self.undone.drain(..).rev().find_map(|undone| match undone {
+            Undone::Node(node) if node >= imported => Some(node),
+            _ => None,
+        });
+        if let Some(node) = earliest {
+            let _ = self.tree.remove_node(node);
+        }
+    }
+}
+
+/// Continues a game from the current node of a tree
+impl TryFrom<GameTree> for Game {
+    type Error = BadukError;
+
+    fn try_from(mut tree: GameTree) -> Result<Game, BadukError> {
+        if tree.cache_state(tree.current).is_none() {
+            return Err(BadukErrorKind::MissingGoBoard.into());
+        }
+        tree.set_strict(true);
+        let imported = tree.count_nodes();
+        Ok(Game { tree, undone: vec![], imported })
+    }
+}
@@ -1,8 +1,31 @@
+use crate::{BadukError, BadukErrorKind};
+use crate::sgf;
+use std::convert::TryFrom;
 use std::fmt;
 
 #[derive(Eq, PartialEq, Clone, Copy, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Position(u32, u32);
 
+/// Ways of writing a position as text.
+///
+/// `Position` itself reads and writes the column and row as they are, without knowing the size
+/// of the board. GTP rows and Japanese columns are counted from the other side of the board, so
+/// use `Coordinates` to convert to and from what an engine or a book means.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Notation {
+    /// A letter for the column, skipping `I`, and the row number, like `D4`. Columns past `Z`
+    /// continue with `AA`, `AB` and so on. Rows are counted from the bottom of the board.
+    Gtp,
+    /// Two letters, `a` to `z` followed by `A` to `Z`, like `dd`, counted from the top left
+    Sgf,
+    /// Column and row numbers, like `4-4`, counted from the top left
+    Numeric,
+    /// Full width numerals for the column and kanji numerals for the row, like `４四`. Columns
+    /// are counted from the right side of the board and rows from the top.
+    Japanese,
+}
+
 impl Position {
     pub fn x(&self) -> u32 {
         self.0
@@ -11,6 +34,217 @@ impl Position {
     pub fn y(&self) -> u32 {
         self.1
     }
+
+    /// Parses a move in any notation, where `pass` gives `None`
+    pub fn parse_move(value: &str) -> Result<Option<Position>, BadukError> {
+        let value = value.trim();
+        if value.eq_ignore_ascii_case("pass") {
+            return Ok(None);
+        }
+        let notation = if !value.is_ascii() {
+            Notation::Japanese
+        } else if value.contains('-') {
+            Notation::Numeric
+        } else if value.bytes().any(|b| b.is_ascii_digit()) {
+            Notation::Gtp
+        } else {
+            Notation::Sgf
+        };
+        Position::parse_notation(value, notation).map(Some)
+    }
+
+    /// Parses a position written in a specific notation, reading the column and row as they are
+    /// written. For GTP and Japanese positions, use `Coordinates::parse` to get the point on the
+    /// board instead.
+    pub fn parse_notation(value: &str, notation: Notation) -> Result<Position, BadukError> {
+        let pos = match notation {
+            Notation::Gtp => parse_gtp(value),
+            Notation::Sgf => sgf::parse_point(value).map(Position::from),
+            Notation::Numeric => parse_numeric(value),
+            Notation::Japanese => parse_japanese(value),
+        };
+        pos.filter(|pos| pos.x() >= 1 && pos.y() >= 1 && pos.x() <= MAX_COORDINATE && pos.y() <= MAX_COORDINATE)
+            .ok_or_else(|| invalid_coordinate(value))
+    }
+
+    /// Writes a position in a notation, failing for coordinates outside 1 to 52
+    pub fn format(&self, notation: Notation) -> Result<String, BadukError> {
+        let invalid = || BadukError::from(BadukErrorKind::InvalidPosition(*self));
+        if self.x() < 1 || self.y() < 1 || self.x() > MAX_COORDINATE || self.y() > MAX_COORDINATE {
+            return Err(invalid());
+        }
+        match notation {
+            Notation::Gtp => Ok(format!("{}{}", column_label(self.x()).ok_or_else(invalid)?, self.y())),
+            Notation::Sgf => sgf::point_to_string(*self).ok_or_else(invalid),
+            Notation::Numeric => Ok(format!("{}-{}", self.x(), self.y())),
+            Notation::Japanese => Ok(format!("{}{}", full_width_number(self.x()), kanji_number(self.y()))),
+        }
+    }
+
+    /// Writes a move in a notation, where `None` is written as `pass`
+    pub fn format_move(pos: Option<Position>, notation: Notation) -> Result<String, BadukError> {
+        match pos {
+            Some(pos) => pos.format(notation),
+            None => Ok("pass".to_string()),
+        }
+    }
+}
+
+/// The largest coordinate SGF can describe
+const MAX_COORDINATE: u32 = 52;
+
+const COLUMN_LETTERS: &[u8] = b"ABCDEFGHJKLMNOPQRSTUVWXYZ";
+
+/// Gets the label for a column, `A` to `Z` skipping `I`, followed by `AA`, `AB` and so on, or
+/// `None` for 0 and columns past `ZZ`
+pub(crate) fn column_label(x: u32) -> Option<String> {
+    let count = COLUMN_LETTERS.len() as u32;
+    let letter = |index: u32| COLUMN_LETTERS.get(index as usize).map(|letter| *letter as char);
+    let index = x.checked_sub(1)?;
+    if index < count {
+        letter(index).map(String::from)
+    } else {
+        Some(format!("{}{}", letter(index / count - 1)?, letter(index % count)?))
+    }
+}
+
+fn parse_gtp(value: &str) -> Option<Position> {
+    let split = value.find(|c: char| c.is_ascii_digit())?;
+    let (letters, number) = value.split_at(split);
+    let count = COLUMN_LETTERS.len() as u32;
+    let index = |c: u8| {
+        COLUMN_LETTERS
+            .iter()
+            .position(|letter| *letter == c.to_ascii_uppercase())
+            .map(|index| index as u32)
+    };
+    let x = match letters.as_bytes() {
+        [c] => index(*c)? + 1,
+        [first, second] => (index(*first)? + 1) * count + index(*second)? + 1,
+        _ => return None,
+    };
+    if !number.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some(Position(x, number.parse().ok()?))
+}
+
+fn parse_numeric(value: &str) -> Option<Position> {
+    let (x, y) = value.split_once('-')?;
+    let parse = |n: &str| -> Option<u32> {
+        if n.is_empty() || !n.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        n.parse().ok()
+    };
+    Some(Position(parse(x)?, parse(y)?))
+}
+
+fn parse_japanese(value: &str) -> Option<Position> {
+    let split = value
+        .char_indices()
+        .find(|(_, c)| digit_value(*c).is_none())
+        .map(|(index, _)| index)?;
+    let (column, row) = value.split_at(split);
+    let x = column.chars().try_fold(0u32, |x, c| Some(x * 10 + digit_value(c)?))?;
+    let y = (1..=MAX_COORDINATE).find(|y| kanji_number(*y) == row)?;
+    Some(Position(x, y))
+}
+
+/// Gets the value of an ASCII or full width digit
+fn digit_value(c: char) -> Option<u32> {
+    match c {
+        '0'..='9' => c.to_digit(10),
+        '０'..='９' => Some(c as u32 - '０' as u32),
+        _ => None,
+    }
+}
+
+fn full_width_number(n: u32) -> String {
+    n.to_string()
+        .chars()
+        .map(|c| std::char::from_u32('０' as u32 + c.to_digit(10).unwrap_or(0)).unwrap_or(c))
+        .collect()
+}
+
+/// Writes a number in kanji, like `十九` for 19
+fn kanji_number(n: u32) -> String {
+    const DIGITS: [&str; 10] = ["", "一", "二", "三", "四", "五", "六", "七", "八", "九"];
+    let (tens, ones) = ((n / 10) as usize, (n % 10) as usize);
+    match tens {
+        0 => DIGITS[ones].to_string(),
+        1 => format!("十{}", DIGITS[ones]),
+        _ => format!("{}十{}", DIGITS[tens % 10], DIGITS[ones]),
+    }
+}
+
+/// Converts positions to and from text for a board of a given size, turning the rows or columns
+/// around for notations that count them from the other side of the board
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Coordinates {
+    width: u32,
+    height: u32,
+}
+
+impl Coordinates {
+    pub fn new(width: u32, height: u32) -> Coordinates {
+        Coordinates { width, height }
+    }
+
+    /// Parses a position on the board
+    pub fn parse(&self, value: &str, notation: Notation) -> Result<Position, BadukError> {
+        match self.parse_move(value, notation)? {
+            Some(pos) => Ok(pos),
+            None => Err(invalid_coordinate(value)),
+        }
+    }
+
+    /// Parses a move on the board, where a pass gives `None`. Besides `pass`, SGF passes are
+    /// written as an empty value, or as `tt` on boards up to 19x19.
+    pub fn parse_move(&self, value: &str, notation: Notation) -> Result<Option<Position>, BadukError> {
+        let trimmed = value.trim();
+        let is_sgf_pass = trimmed.is_empty() || (trimmed == "tt" && self.width <= 19 && self.height <= 19);
+        if trimmed.eq_ignore_ascii_case("pass") || (notation == Notation::Sgf && is_sgf_pass) {
+            return Ok(None);
+        }
+        let pos = Position::parse_notation(trimmed, notation).map_err(|_| invalid_coordinate(value))?;
+        if pos.x() > self.width || pos.y() > self.height {
+            return Err(invalid_coordinate(value));
+        }
+        Ok(Some(self.turn(pos, notation)))
+    }
+
+    /// Writes a position on the board, failing for positions outside it
+    pub fn format(&self, pos: Position, notation: Notation) -> Result<String, BadukError> {
+        if pos.x() < 1 || pos.y() < 1 || pos.x() > self.width || pos.y() > self.height {
+            return Err(BadukErrorKind::InvalidPosition(pos).into());
+        }
+        self.turn(pos, notation).format(notation)
+    }
+
+    /// Writes a move on the board, where `None` is a pass. SGF passes are written as an empty
+    /// value.
+    pub fn format_move(&self, pos: Option<Position>, notation: Notation) -> Result<String, BadukError> {
+        match (pos, notation) {
+            (Some(pos), _) => self.format(pos, notation),
+            (None, Notation::Sgf) => Ok(String::new()),
+            (None, _) => Ok("pass".to_string()),
+        }
+    }
+
+    /// Converts between the internal position and the one written in a notation. Turning a
+    /// position twice gives back the original.
+    fn turn(&self, pos: Position, notation: Notation) -> Position {
+        match notation {
+            Notation::Gtp => Position(pos.x(), self.height + 1 - pos.y()),
+            Notation::Japanese => Position(self.width + 1 - pos.x(), pos.y()),
+            Notation::Sgf | Notation::Numeric => pos,
+        }
+    }
+}
+
+fn invalid_coordinate(value: &str) -> BadukError {
+    BadukErrorKind::InvalidCoordinate(value.to_string()).into()
 }
 
 impl From<(u32, u32)> for Position {
@@ -37,16 +271,23 @@ impl From<(i32, i32)> for Position {
     }
 }
 
-impl From<&str> for Position {
-    fn from(pos: &str) -> Position {
-        let (letter, number) = pos.split_at(1);
-        let mut x = letter.to_lowercase().as_bytes()[0] - 96;
-        // The letter 'I' is skipped
-        if x >= 9 {
-            x -= 1;
+impl std::str::FromStr for Position {
+    type Err = BadukError;
+
+    /// Parses a position in GTP (`D4`), SGF (`dd`) or numeric (`4-4`) notation
+    fn from_str(value: &str) -> Result<Position, BadukError> {
+        match Position::parse_move(value)? {
+            Some(pos) => Ok(pos),
+            None => Err(invalid_coordinate(value)),
         }
-        let y = number.parse().unwrap();
-        Position(x as u32, y)
+    }
+}
+
+impl TryFrom<&str> for Position {
+    type Error = BadukError;
+
+    fn try_from(value: &str) -> Result<Position, BadukError> {
+        value.parse()
     }
 }
 
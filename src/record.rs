@@ -0,0 +1,194 @@
+// Game records shared by the readers and writers of the GIB, NGF and UGF formats.
+//
+// These formats only hold a single line of moves with a few game information fields. Reading
+// turns the fields into SGF properties first, so the tokens are the same as when importing SGF.
+
+use crate::{GameTree, GameState, GameResult, BadukError, BadukErrorKind, Color, Position, SgfToken, fixed_handicap_positions};
+use crate::sgf;
+use sgf_parser::{Action, RuleSet};
+use std::ops::RangeInclusive;
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Record {
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) name: Option<String>,
+    pub(crate) event: Option<String>,
+    pub(crate) place: Option<String>,
+    pub(crate) date: Option<String>,
+    pub(crate) black_player: Option<String>,
+    pub(crate) white_player: Option<String>,
+    pub(crate) black_rank: Option<String>,
+    pub(crate) white_rank: Option<String>,
+    pub(crate) komi: Option<f32>,
+    pub(crate) handicap: u32,
+    pub(crate) result: Option<GameResult>,
+    pub(crate) rules: Option<RuleSet>,
+    pub(crate) setup: Vec<(Color, Position)>,
+    /// Moves in order, where passes have no position
+    pub(crate) moves: Vec<(Color, Option<Position>)>,
+}
+
+impl Record {
+    pub(crate) fn new(width: u32, height: u32) -> Record {
+        Record { width, height, ..Record::default() }
+    }
+
+    /// Builds a tree from the record, failing for points SGF can not hold
+    pub(crate) fn into_game_tree(self) -> Result<GameTree, BadukError> {
+        let size = if self.width == self.height {
+            self.width.to_string()
+        } else {
+            format!("{}:{}", self.width, self.height)
+        };
+        let mut root = vec![("SZ", size)];
+        let texts = vec![
+            ("GN", &self.name),
+            ("EV", &self.event),
+            ("PC", &self.place),
+            ("DT", &self.date),
+            ("PB", &self.black_player),
+            ("BR", &self.black_rank),
+            ("PW", &self.white_player),
+            ("WR", &self.white_rank),
+        ];
+        for (ident, value) in texts {
+            if let Some(value) = value {
+                root.push((ident, sgf::escape_text(value)));
+            }
+        }
+        if let Some(komi) = self.komi {
+            root.push(("KM", komi.to_string()));
+        }
+        if self.handicap >= 2 {
+            root.push(("HA", self.handicap.to_string()));
+        }
+        if let Some(result) = self.result {
+            root.push(("RE", result.to_string()));
+        }
+        if let Some(ref rules) = self.rules {
+            root.push(("RU", sgf::escape_text(&rules.to_string())));
+        }
+        let mut setup = self.setup.clone();
+        // Handicap stones go on the standard points when the record does not list them
+        if self.handicap >= 2 && !setup.iter().any(|(color, _)| *color == Color::Black) {
+            let stones = fixed_handicap_positions(self.width, self.height, self.handicap).unwrap_or_default();
+            setup.splice(0..0, stones.into_iter().map(|pos| (Color::Black, pos)));
+        }
+        for (color, pos) in setup {
+            root.push((color_property(color, "AB", "AW"), point(pos)?));
+        }
+
+        let mut game = GameTree::default();
+        let mut node = game.root;
+        for (ident, value) in root {
+            sgf::read_property(&mut game, node, ident, &value);
+        }
+        if game.nodes[node].state.is_none() {
+            game.nodes[node].state = Some(GameState::default());
+        }
+        for (color, pos) in self.moves {
+            node = game.create_new_node(node);
+            let value = pos.map(point).transpose()?.unwrap_or_default();
+            sgf::read_property(&mut game, node, color_property(color, "B", "W"), &value);
+        }
+        game.release_states();
+        Ok(game)
+    }
+
+    /// Reads the game information and the main line of a tree
+    pub(crate) fn from_game_tree(game: &GameTree) -> Record {
+        let root = &game.nodes[game.root];
+        let (width, height) = root.state.as_ref().map_or((19, 19), |state| state.dimensions());
+        let info = game.game_info();
+        let mut record = Record::new(width, height);
+        for token in &root.tokens {
+            match token {
+                SgfToken::GameName(name) => record.name = Some(name.clone()),
+                SgfToken::Place(place) => record.place = Some(place.clone()),
+                SgfToken::Handicap(stones) => record.handicap = *stones,
+                _ => {}
+            }
+        }
+        record.event = info.event;
+        record.date = info.date;
+        record.black_player = info.black_player;
+        record.white_player = info.white_player;
+        record.black_rank = info.black_rank;
+        record.white_rank = info.white_rank;
+        record.komi = info.komi;
+        record.result = info.result;
+        record.rules = info.rules;
+        record.setup = game
+            .get_setup(game.root)
+            .into_iter()
+            .filter_map(|(pos, color)| color.map(|color| (color, pos)))
+            .collect();
+
+        let mut end = game.root;
+        while let Some(child) = game.nodes[end].children.first() {
+            end = *child;
+        }
+        record.moves = game
+            .path_to(end)
+            .into_iter()
+            .flat_map(|index| game.nodes[index].tokens.iter())
+            .filter_map(|token| match token {
+                SgfToken::Move { color, action: Action::Move(x, y) } => {
+                    let pos = Position::from((u32::from(*x), u32::from(*y)));
+                    Some((*color, Some(pos).filter(|pos| pos.x() <= width && pos.y() <= height)))
+                }
+                SgfToken::Move { color, action: Action::Pass } => Some((*color, None)),
+                _ => None,
+            })
+            .collect();
+        record
+    }
+
+    /// Checks that the board is square, with a size a format can hold
+    pub(crate) fn check_size(&self, sizes: RangeInclusive<u32>) -> Result<(), BadukError> {
+        if self.width != self.height || !sizes.contains(&self.width) {
+            return Err(BadukErrorKind::InvalidInputSize.into());
+        }
+        Ok(())
+    }
+}
+
+/// Decodes a record, using the given charset for data that is not valid UTF-8
+pub(crate) fn decode(input: &[u8], charset: &'static encoding_rs::Encoding) -> String {
+    let encoding = match std::str::from_utf8(input) {
+        Ok(_) => encoding_rs::UTF_8,
+        Err(_) => charset,
+    };
+    let (text, _, _) = encoding.decode(input);
+    text.into_owned()
+}
+
+/// Splits a player like `Lee Sedol 9p` into the name and the rank, if it ends with one
+pub(crate) fn split_rank(player: &str) -> (String, Option<String>) {
+    let player = player.trim();
+    if let Some((name, rank)) = player.rsplit_once(char::is_whitespace) {
+        if is_rank(rank) {
+            return (name.trim().to_string(), Some(rank.to_string()));
+        }
+    }
+    (player.to_string(), None)
+}
+
+/// Checks for ranks like `9p`, `3D` or `15k*`
+fn is_rank(value: &str) -> bool {
+    let value = value.trim_end_matches('*');
+    let digits = value.bytes().take_while(u8::is_ascii_digit).count();
+    digits > 0 && matches!(&value[digits..], "k" | "K" | "d" | "D" | "p" | "P")
+}
+
+fn color_property(color: Color, black: &'static str, white: &'static str) -> &'static str {
+    match color {
+        Color::Black => black,
+        Color::White => white,
+    }
+}
+
+fn point(pos: Position) -> Result<String, BadukError> {
+    sgf::point_to_string(pos).ok_or_else(|| BadukErrorKind::InvalidPosition(pos).into())
+}
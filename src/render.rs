@@ -0,0 +1,161 @@
+use crate::{GameState, Color, Position, star_points};
+use crate::position::column_label;
+use std::fmt;
+
+/// How a board is turned when rendered
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Orientation {
+    /// A1 in the lower left corner, as seen by Black
+    Normal,
+    /// Turned half a circle, as seen by White
+    Rotated,
+    /// Mirrored top to bottom, with A1 in the upper left corner
+    Flipped,
+}
+
+/// Renders a board as text, with coordinates and star points. Columns are labeled with letters,
+/// skipping `I`, and rows are numbered from the bottom, like in GTP.
+///
+/// ```text
+///   A B C D E F G
+/// 7 . . . . . . . 7
+/// 6 . . . . . . . 6
+/// 5 . . + . +(X). 5
+/// ```
+#[derive(Debug, Clone)]
+pub struct TextRenderer<'a> {
+    state: &'a GameState,
+    last_move: Option<Position>,
+    unicode: bool,
+    coordinates: bool,
+    orientation: Orientation,
+}
+
+impl<'a> TextRenderer<'a> {
+    pub fn new(state: &'a GameState) -> TextRenderer<'a> {
+        TextRenderer {
+            state,
+            last_move: None,
+            unicode: false,
+            coordinates: true,
+            orientation: Orientation::Normal,
+        }
+    }
+
+    /// Marks a stone with parentheses, like `(X)`
+    pub fn last_move(mut self, pos: Option<Position>) -> Self {
+        self.last_move = pos;
+        self
+    }
+
+    /// Uses `●` and `○` for stones instead of `X` and `O`
+    pub fn unicode(mut self, unicode: bool) -> Self {
+        self.unicode = unicode;
+        self
+    }
+
+    pub fn coordinates(mut self, coordinates: bool) -> Self {
+        self.coordinates = coordinates;
+        self
+    }
+
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    fn symbol(&self, pos: Position, is_star_point: bool) -> char {
+        match (self.state.get_stone(pos), self.unicode) {
+            (Some(Color::Black), false) => 'X',
+            (Some(Color::White), false) => 'O',
+            (Some(Color::Black), true) => '●',
+            (Some(Color::White), true) => '○',
+            (None, _) if is_star_point => '+',
+            (None, false) => '.',
+            (None, true) => '·',
+        }
+    }
+
+    /// Gets the board position shown at a column and row, both counted from 0 at the top left
+    fn position(&self, column: u32, row: u32) -> Position {
+        let (width, height) = self.state.dimensions();
+        match self.orientation {
+            Orientation::Normal => (column + 1, row + 1).into(),
+            Orientation::Rotated => (width - column, height - row).into(),
+            Orientation::Flipped => (column + 1, height - row).into(),
+        }
+    }
+}
+
+/// Gets the label for a row, counted from the bottom of the board
+pub(crate) fn row_label(y: u32, height: u32) -> String {
+    (height + 1 - y).to_string()
+}
+
+impl<'a> fmt::Display for TextRenderer<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (width, height) = self.state.dimensions();
+        let stars = star_points(width, height);
+        let margin = if self.coordinates { height.to_string().len() } else { 0 };
+
+        let columns: Vec<String> = (0..width).map(|column| column_label(self.position(column, 0).x()).unwrap_or_default()).collect();
+        let header_lines = columns.iter().map(String::len).max().unwrap_or(0);
+        let write_header = |f: &mut fmt::Formatter| -> fmt::Result {
+            for line in 0..header_lines {
+                let header: String = columns
+                    .iter()
+                    .map(|label| {
+                        // Shorter labels are aligned with the last line
+                        let offset = header_lines - label.len();
+                        let c = if line < offset { ' ' } else { label.as_bytes()[line - offset] as char };
+                        format!(" {}", c)
+                    })
+                    .collect();
+                writeln!(f, "{:margin$}{}", "", header.trim_end(), margin = margin)?;
+            }
+            Ok(())
+        };
+
+        if self.coordinates {
+            write_header(f)?;
+        }
+        for row in 0..height {
+            let label = row_label(self.position(0, row).y(), height);
+            let mut line = String::new();
+            if self.coordinates {
+                line.push_str(&format!("{:>margin$}", label, margin = margin));
+            }
+            let mut previous = None;
+            for column in 0..width {
+                let pos = self.position(column, row);
+                line.push(match (previous, self.last_move) {
+                    (_, Some(last)) if last == pos => '(',
+                    (Some(prev), Some(last)) if last == prev => ')',
+                    _ => ' ',
+                });
+                line.push(self.symbol(pos, stars.contains(&pos)));
+                previous = Some(pos);
+            }
+            if previous.is_some() && previous == self.last_move {
+                line.push(')');
+            } else if self.coordinates {
+                line.push(' ');
+            }
+            if self.coordinates {
+                line.push_str(&label);
+            }
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        if self.coordinates {
+            write_header(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl GameState {
+    /// Gets a renderer for drawing the board as text, see `TextRenderer`
+    pub fn render(&self) -> TextRenderer<'_> {
+        TextRenderer::new(self)
+    }
+}
@@ -0,0 +1,212 @@
+//! `Serialize` and `Deserialize` implementations, enabled by the `serde` feature.
+//!
+//! The JSON shapes are:
+//!
+//! - `Position`: `[x, y]`, 1-based from the top left
+//! - `Captures`: `{"black": 0, "white": 2}`, the number of stones of each color captured
+//! - `GameState`: `{"width": 9, "height": 9, "board": [".x.o.....", ...], "captures": {...},
+//!   "to_move": "B", "ko": [x, y], "komi": 6.5}`, with one string per row using `x` for black,
+//!   `o` for white and `.` for empty points. `to_move`, `ko` and `komi` may be `null`.
+//! - tokens: `{"property": "B", "value": "dd"}`, the SGF property identifier and the value as
+//!   written in SGF, including SGF escapes
+//! - `GameTreeNode`: `{"parent": 0, "children": [2], "tokens": [...], "state": {...}}`, where
+//!   `state` is left out for nodes without a stored board
+//! - `GameTree`: `{"root": 0, "current": 3, "nodes": [...], "checkpoint_interval": 16,
+//!   "strict": false}`, where `checkpoint_interval` is left out when every node stores its board
+
+use crate::{GameState, GameTree, GameTreeNode, Captures, Color, Position, SgfToken};
+use crate::sgf;
+use serde::de::{self, Deserializer};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct StateData {
+    width: u32,
+    height: u32,
+    board: Vec<String>,
+    captures: Captures,
+    #[serde(with = "color")]
+    to_move: Option<Color>,
+    ko: Option<Position>,
+    komi: Option<f32>,
+}
+
+impl Serialize for GameState {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let board = (1..=self.height)
+            .map(|y| {
+                (1..=self.width)
+                    .map(|x| match self.get_stone((x, y)) {
+                        Some(Color::Black) => 'x',
+                        Some(Color::White) => 'o',
+                        None => '.',
+                    })
+                    .collect()
+            })
+            .collect();
+        StateData {
+            width: self.width,
+            height: self.height,
+            board,
+            captures: self.captures.clone(),
+            to_move: self.to_move,
+            ko: self.ko,
+            komi: self.komi,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for GameState {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<GameState, D::Error> {
+        let data = StateData::deserialize(deserializer)?;
+        if data.width > GameState::MAX_SIZE || data.height > GameState::MAX_SIZE {
+            return Err(de::Error::custom("board is too large"));
+        }
+        if data.board.len() != data.height as usize {
+            return Err(de::Error::custom("number of rows does not match the height"));
+        }
+        let mut state = GameState::new(data.width, data.height);
+        for (y, row) in (1..).zip(&data.board) {
+            if row.chars().count() != data.width as usize {
+                return Err(de::Error::custom("length of a row does not match the width"));
+            }
+            for (x, c) in (1..).zip(row.chars()) {
+                let color = match c {
+                    'x' => Color::Black,
+                    'o' => Color::White,
+                    '.' => continue,
+                    _ => return Err(de::Error::custom(format!("invalid point '{}'", c))),
+                };
+                state = state.add_stone((x, y), color).map_err(de::Error::custom)?;
+            }
+        }
+        if let Some(ko) = data.ko {
+            if !state.is_valid_position(ko) || state.get_stone(ko).is_some() {
+                return Err(de::Error::custom("ko is not an empty point on the board"));
+            }
+        }
+        state.captures = data.captures;
+        state.to_move = data.to_move;
+        state.ko = data.ko;
+        state.komi = data.komi;
+        Ok(state)
+    }
+}
+
+#[derive(Serialize)]
+struct TreeRef<'a> {
+    root: usize,
+    current: usize,
+    nodes: &'a [GameTreeNode],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    checkpoint_interval: Option<usize>,
+    strict: bool,
+}
+
+#[derive(Deserialize)]
+struct TreeData {
+    root: usize,
+    current: usize,
+    nodes: Vec<GameTreeNode>,
+    #[serde(default)]
+    checkpoint_interval: Option<usize>,
+    #[serde(default)]
+    strict: bool,
+}
+
+impl Serialize for GameTree {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        TreeRef {
+            root: self.root,
+            current: self.current,
+            nodes: &self.nodes,
+            checkpoint_interval: self.checkpoint_interval(),
+            strict: self.strict,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for GameTree {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<GameTree, D::Error> {
+        let data = TreeData::deserialize(deserializer)?;
+        let nodes = data.nodes;
+        let len = nodes.len();
+        if data.root >= len || data.current >= len || nodes[data.root].parent.is_some() {
+            return Err(de::Error::custom("invalid root or current node"));
+        }
+        // Parents always come before their children, the same as in trees built by the crate
+        for index in 0..len {
+            if let Some(parent) = nodes[index].parent {
+                if parent >= index || !nodes[parent].children.contains(&index) {
+                    return Err(de::Error::custom(format!("invalid parent of node {}", index)));
+                }
+            } else if index != data.root {
+                return Err(de::Error::custom(format!("node {} has no parent", index)));
+            }
+            if nodes[index].children.iter().any(|child| *child >= len || nodes[*child].parent != Some(index)) {
+                return Err(de::Error::custom(format!("invalid children of node {}", index)));
+            }
+        }
+        let mut tree = GameTree::default();
+        tree.root = data.root;
+        tree.current = data.current;
+        tree.nodes = nodes;
+        tree.strict = data.strict;
+        if data.checkpoint_interval.is_some() {
+            tree.set_checkpoint_interval(data.checkpoint_interval);
+        }
+        Ok(tree)
+    }
+}
+
+/// Serializes tokens as SGF properties
+pub(crate) mod tokens {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct Property {
+        property: String,
+        value: String,
+    }
+
+    pub(crate) fn serialize<S: Serializer>(tokens: &[SgfToken], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(tokens.iter().map(|token| {
+            let (property, value) = sgf::token_to_property(token);
+            Property { property, value }
+        }))
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<SgfToken>, D::Error> {
+        let properties = Vec::<Property>::deserialize(deserializer)?;
+        Ok(properties
+            .iter()
+            .flat_map(|property| sgf::parse_property_value(&property.property, &property.value))
+            .collect())
+    }
+}
+
+/// Serializes colors as "B" or "W"
+mod color {
+    use super::*;
+
+    pub(super) fn serialize<S: Serializer>(color: &Option<Color>, serializer: S) -> Result<S::Ok, S::Error> {
+        color
+            .map(|color| match color {
+                Color::Black => "B",
+                Color::White => "W",
+            })
+            .serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Color>, D::Error> {
+        match Option::<String>::deserialize(deserializer)?.as_deref() {
+            None => Ok(None),
+            Some("B") => Ok(Some(Color::Black)),
+            Some("W") => Ok(Some(Color::White)),
+            Some(value) => Err(de::Error::custom(format!("invalid color '{}'", value))),
+        }
+    }
+}
@@ -1,33 +1,974 @@
 // https://www.red-bean.com/sgf/
+//
+// In-crate SGF reading and writing, covering the FF[4] properties used for Go.
+//
+// Properties modelled by `SgfToken` are converted to their typed variant, with text values
+// unescaped. Every other property is kept as `SgfToken::Unknown((identifier, value))`, where the
+// value is the raw, still escaped, text between the brackets. Values that do not match the type
+// the spec defines for a known property are kept as `SgfToken::Invalid` in the same way.
 
-use crate::{Game};
-
-pub struct SgfParser {};
-
-enum Token {
-    EV, // Event
-    GM, // Game Type, 1 == Go
-    US, // Game/Program provider
-    CP, // Copyright
-    GN, // Game Name
-    PW, // Player White
-    WR, // White Rank
-    PB, // Player Black,
-    BR, // Black Rank
-    PC, // Place for game
-    DT, // Game Date
-    SZ, // Size
-    TM, // Time limit
-    KM, // Komi
-    C,  // Comment
-    B,  // Black Move
-    BL, // Black time left
-    W,  // White Move
-    WL  // White time left
-}
-
-impl SgfParser {
-    pub parse(sgf: &str) -> Option<Game> {
-        None
+use crate::{GameTree, GameState, BadukError, Color, Position, SgfToken};
+use crate::game::GameTreeIndex;
+use crate::info::GameResult;
+use derive_more::*;
+use sgf_parser::{Action, DisplayNodes, Encoding, Game, RuleSet};
+use std::error::Error;
+use std::io::BufRead;
+use std::ops::RangeInclusive;
+
+/// Error found while reading SGF data
+#[derive(Debug, Display, Eq, PartialEq)]
+#[display(fmt = "{} at byte {}", message, offset)]
+pub struct SgfError {
+    pub message: &'static str,
+    pub offset: usize,
+}
+
+impl Error for SgfError {}
+
+/// Property types as defined by the SGF specification
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PropertyType {
+    Move,
+    Setup,
+    Root,
+    GameInfo,
+    NodeAnnotation,
+    MoveAnnotation,
+    Markup,
+    Timing,
+    Miscellaneous,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum ValueType {
+    None,
+    Number,
+    Real,
+    Double,
+    Color,
+    SimpleText,
+    Text,
+    Move,
+    PointList,
+    EmptyPointList,
+    PointPairList,
+    Label,
+    Application,
+    Size,
+    Figure,
+}
+
+const PROPERTIES: &[(&str, PropertyType, ValueType)] = &[
+    ("B", PropertyType::Move, ValueType::Move),
+    ("W", PropertyType::Move, ValueType::Move),
+    ("KO", PropertyType::Move, ValueType::None),
+    ("MN", PropertyType::Move, ValueType::Number),
+    ("AB", PropertyType::Setup, ValueType::PointList),
+    ("AW", PropertyType::Setup, ValueType::PointList),
+    ("AE", PropertyType::Setup, ValueType::PointList),
+    ("PL", PropertyType::Setup, ValueType::Color),
+    ("C", PropertyType::NodeAnnotation, ValueType::Text),
+    ("DM", PropertyType::NodeAnnotation, ValueType::Double),
+    ("GB", PropertyType::NodeAnnotation, ValueType::Double),
+    ("GW", PropertyType::NodeAnnotation, ValueType::Double),
+    ("HO", PropertyType::NodeAnnotation, ValueType::Double),
+    ("N", PropertyType::NodeAnnotation, ValueType::SimpleText),
+    ("UC", PropertyType::NodeAnnotation, ValueType::Double),
+    ("V", PropertyType::NodeAnnotation, ValueType::Real),
+    ("BM", PropertyType::MoveAnnotation, ValueType::Double),
+    ("DO", PropertyType::MoveAnnotation, ValueType::None),
+    ("IT", PropertyType::MoveAnnotation, ValueType::None),
+    ("TE", PropertyType::MoveAnnotation, ValueType::Double),
+    ("AR", PropertyType::Markup, ValueType::PointPairList),
+    ("CR", PropertyType::Markup, ValueType::PointList),
+    ("DD", PropertyType::Markup, ValueType::EmptyPointList),
+    ("LB", PropertyType::Markup, ValueType::Label),
+    ("LN", PropertyType::Markup, ValueType::PointPairList),
+    ("MA", PropertyType::Markup, ValueType::PointList),
+    ("SL", PropertyType::Markup, ValueType::PointList),
+    ("SQ", PropertyType::Markup, ValueType::PointList),
+    ("TR", PropertyType::Markup, ValueType::PointList),
+    ("AP", PropertyType::Root, ValueType::Application),
+    ("CA", PropertyType::Root, ValueType::SimpleText),
+    ("FF", PropertyType::Root, ValueType::Number),
+    ("GM", PropertyType::Root, ValueType::Number),
+    ("ST", PropertyType::Root, ValueType::Number),
+    ("SZ", PropertyType::Root, ValueType::Size),
+    ("AN", PropertyType::GameInfo, ValueType::SimpleText),
+    ("BR", PropertyType::GameInfo, ValueType::SimpleText),
+    ("BT", PropertyType::GameInfo, ValueType::SimpleText),
+    ("CP", PropertyType::GameInfo, ValueType::SimpleText),
+    ("DT", PropertyType::GameInfo, ValueType::SimpleText),
+    ("EV", PropertyType::GameInfo, ValueType::SimpleText),
+    ("GC", PropertyType::GameInfo, ValueType::Text),
+    ("GN", PropertyType::GameInfo, ValueType::SimpleText),
+    ("HA", PropertyType::GameInfo, ValueType::Number),
+    ("KM", PropertyType::GameInfo, ValueType::Real),
+    ("ON", PropertyType::GameInfo, ValueType::SimpleText),
+    ("OT", PropertyType::GameInfo, ValueType::SimpleText),
+    ("PB", PropertyType::GameInfo, ValueType::SimpleText),
+    ("PC", PropertyType::GameInfo, ValueType::SimpleText),
+    ("PW", PropertyType::GameInfo, ValueType::SimpleText),
+    ("RE", PropertyType::GameInfo, ValueType::SimpleText),
+    ("RO", PropertyType::GameInfo, ValueType::SimpleText),
+    ("RU", PropertyType::GameInfo, ValueType::SimpleText),
+    ("SO", PropertyType::GameInfo, ValueType::SimpleText),
+    ("TM", PropertyType::GameInfo, ValueType::Real),
+    ("US", PropertyType::GameInfo, ValueType::SimpleText),
+    ("WR", PropertyType::GameInfo, ValueType::SimpleText),
+    ("WT", PropertyType::GameInfo, ValueType::SimpleText),
+    ("BL", PropertyType::Timing, ValueType::Real),
+    ("OB", PropertyType::Timing, ValueType::Number),
+    ("OW", PropertyType::Timing, ValueType::Number),
+    ("WL", PropertyType::Timing, ValueType::Real),
+    ("FG", PropertyType::Miscellaneous, ValueType::Figure),
+    ("PM", PropertyType::Miscellaneous, ValueType::Number),
+    ("VW", PropertyType::Miscellaneous, ValueType::EmptyPointList),
+    ("TB", PropertyType::Miscellaneous, ValueType::EmptyPointList),
+    ("TW", PropertyType::Miscellaneous, ValueType::EmptyPointList),
+];
+
+/// Gets the type of a FF[4] Go property, or `None` for properties outside the specification
+pub fn property_type(identifier: &str) -> Option<PropertyType> {
+    PROPERTIES
+        .iter()
+        .find(|(ident, _, _)| *ident == identifier)
+        .map(|(_, property_type, _)| *property_type)
+}
+
+fn value_type(identifier: &str) -> Option<ValueType> {
+    PROPERTIES
+        .iter()
+        .find(|(ident, _, _)| *ident == identifier)
+        .map(|(_, _, value_type)| *value_type)
+}
+
+/// Converts a property identifier and a raw property value into tokens.
+///
+/// Most properties give a single token, but compressed point lists, like `SQ[aa:cc]`, are
+/// expanded to one token per point for markup modelled by `SgfToken`. The setup properties keep
+/// them as a single `SgfToken::Unknown`, which `GameTree` applies point by point.
+pub fn parse_property_value(identifier: &str, value: &str) -> Vec<SgfToken> {
+    let invalid = || vec![SgfToken::Invalid((identifier.to_string(), value.to_string()))];
+    let unknown = || vec![SgfToken::Unknown((identifier.to_string(), value.to_string()))];
+    let text = || unescape_text(value);
+    match identifier {
+        "B" | "W" => {
+            let color = if identifier == "B" { Color::Black } else { Color::White };
+            match parse_move(value) {
+                Some(action) => vec![SgfToken::Move { color, action }],
+                None => invalid(),
+            }
+        }
+        "AB" | "AW" => {
+            let color = if identifier == "AB" { Color::Black } else { Color::White };
+            match parse_point(value) {
+                Some(coordinate) => vec![SgfToken::Add { color, coordinate }],
+                // Compressed point lists are kept as written, so they are written back the same way
+                None if parse_point_list(value).is_some() => unknown(),
+                None => invalid(),
+            }
+        }
+        "AE" => match parse_point_list(value) {
+            Some(_) => unknown(),
+            None => invalid(),
+        },
+        "SQ" | "TR" => match parse_point_list(value) {
+            Some(points) => points
+                .into_iter()
+                .map(|coordinate| match identifier {
+                    "SQ" => SgfToken::Square { coordinate },
+                    _ => SgfToken::Triangle { coordinate },
+                })
+                .collect(),
+            None => invalid(),
+        },
+        "LB" => match split_composed(value) {
+            Some((point, label)) => match parse_point(point) {
+                Some(coordinate) => vec![SgfToken::Label { label: unescape_text(label), coordinate }],
+                None => invalid(),
+            },
+            None => invalid(),
+        },
+        "BL" | "WL" => {
+            let color = if identifier == "BL" { Color::Black } else { Color::White };
+            match value.trim().parse() {
+                Ok(time) => vec![SgfToken::Time { color, time }],
+                Err(_) if value.trim().parse::<f64>().is_ok() => unknown(),
+                Err(_) => invalid(),
+            }
+        }
+        "OB" | "OW" => {
+            let color = if identifier == "OB" { Color::Black } else { Color::White };
+            match value.trim().parse() {
+                Ok(moves) => vec![SgfToken::MovesRemaining { color, moves }],
+                Err(_) => invalid(),
+            }
+        }
+        "PB" | "PW" => {
+            let color = if identifier == "PB" { Color::Black } else { Color::White };
+            vec![SgfToken::PlayerName { color, name: text() }]
+        }
+        "BR" | "WR" => {
+            let color = if identifier == "BR" { Color::Black } else { Color::White };
+            vec![SgfToken::PlayerRank { color, rank: text() }]
+        }
+        "RE" => match text().parse::<GameResult>() {
+            Ok(result) => vec![result.to_token()],
+            Err(_) => invalid(),
+        },
+        "KM" => match value.trim().parse() {
+            Ok(komi) => vec![SgfToken::Komi(komi)],
+            Err(_) => invalid(),
+        },
+        "HA" => match value.trim().parse() {
+            Ok(stones) => vec![SgfToken::Handicap(stones)],
+            Err(_) => invalid(),
+        },
+        "TM" => match value.trim().parse() {
+            Ok(time) => vec![SgfToken::TimeLimit(time)],
+            Err(_) if value.trim().parse::<f64>().is_ok() => unknown(),
+            Err(_) => invalid(),
+        },
+        "SZ" => {
+            let size = match split_composed(value) {
+                Some((width, height)) => width.trim().parse().ok().zip(height.trim().parse().ok()),
+                None => value.trim().parse().ok().map(|size| (size, size)),
+            };
+            match size {
+                Some((width, height)) if (1..=52).contains(&width) && (1..=52).contains(&height) => {
+                    vec![SgfToken::Size(width, height)]
+                }
+                _ => invalid(),
+            }
+        }
+        "FF" => match value.trim().parse() {
+            Ok(version) if (1..=4).contains(&version) => vec![SgfToken::FileFormat(version)],
+            _ => invalid(),
+        },
+        "GM" => match value.trim().parse() {
+            Ok(1) => vec![SgfToken::Game(Game::Go)],
+            Ok(game) => vec![SgfToken::Game(Game::Other(game))],
+            Err(_) => invalid(),
+        },
+        "ST" => match value.trim() {
+            "0" => vec![SgfToken::VariationDisplay { nodes: DisplayNodes::Children, on_board_display: true }],
+            "1" => vec![SgfToken::VariationDisplay { nodes: DisplayNodes::Siblings, on_board_display: true }],
+            "2" => vec![SgfToken::VariationDisplay { nodes: DisplayNodes::Children, on_board_display: false }],
+            "3" => vec![SgfToken::VariationDisplay { nodes: DisplayNodes::Siblings, on_board_display: false }],
+            _ => invalid(),
+        },
+        "CA" => match text().to_lowercase().as_str() {
+            "utf-8" | "utf8" => vec![SgfToken::Charset(Encoding::UTF8)],
+            _ => vec![SgfToken::Charset(Encoding::Other(text()))],
+        },
+        "AP" => match split_composed(value) {
+            Some((name, version)) => vec![SgfToken::Application {
+                name: unescape_text(name),
+                version: unescape_text(version),
+            }],
+            None => vec![SgfToken::Application { name: text(), version: String::new() }],
+        },
+        "RU" => vec![SgfToken::Rule(RuleSet::from(text().as_str()))],
+        "C" => vec![SgfToken::Comment(text())],
+        "EV" => vec![SgfToken::Event(text())],
+        "CP" => vec![SgfToken::Copyright(text())],
+        "GN" => vec![SgfToken::GameName(text())],
+        "PC" => vec![SgfToken::Place(text())],
+        "DT" => vec![SgfToken::Date(text())],
+        "OT" => vec![SgfToken::Overtime(text())],
+        _ => match value_type(identifier) {
+            Some(value_type) if !is_valid_value(value_type, value) => invalid(),
+            _ => unknown(),
+        },
+    }
+}
+
+/// Converts a token to its property identifier and raw property value
+pub fn token_to_property(token: &SgfToken) -> (String, String) {
+    let color_ident = |color: &Color, black: &str, white: &str| match color {
+        Color::Black => black.to_string(),
+        Color::White => white.to_string(),
+    };
+    // Tokens are only built with points SGF can hold, anything else is written as an empty value
+    let point_to_string = |point: (u8, u8)| point_to_string(point).unwrap_or_default();
+    match token {
+        SgfToken::Move { color, action } => {
+            let value = match action {
+                Action::Move(x, y) => point_to_string((*x, *y)),
+                Action::Pass => String::new(),
+            };
+            (color_ident(color, "B", "W"), value)
+        }
+        SgfToken::Add { color, coordinate } => (color_ident(color, "AB", "AW"), point_to_string(*coordinate)),
+        SgfToken::Time { color, time } => (color_ident(color, "BL", "WL"), time.to_string()),
+        SgfToken::MovesRemaining { color, moves } => (color_ident(color, "OB", "OW"), moves.to_string()),
+        SgfToken::PlayerName { color, name } => (color_ident(color, "PB", "PW"), escape_text(name)),
+        SgfToken::PlayerRank { color, rank } => (color_ident(color, "BR", "WR"), escape_text(rank)),
+        SgfToken::Game(game) => {
+            let value = match game {
+                Game::Go => 1,
+                Game::Other(n) => *n,
+            };
+            ("GM".to_string(), value.to_string())
+        }
+        SgfToken::Rule(rules) => ("RU".to_string(), escape_text(&rules.to_string())),
+        SgfToken::Result(outcome) => ("RE".to_string(), GameResult::from_outcome(outcome).to_string()),
+        SgfToken::Komi(komi) => ("KM".to_string(), komi.to_string()),
+        SgfToken::Event(value) => ("EV".to_string(), escape_text(value)),
+        SgfToken::Copyright(value) => ("CP".to_string(), escape_text(value)),
+        SgfToken::GameName(value) => ("GN".to_string(), escape_text(value)),
+        SgfToken::Place(value) => ("PC".to_string(), escape_text(value)),
+        SgfToken::Date(value) => ("DT".to_string(), escape_text(value)),
+        SgfToken::Overtime(value) => ("OT".to_string(), escape_text(value)),
+        SgfToken::Comment(value) => ("C".to_string(), escape_text(value)),
+        SgfToken::VariationDisplay { nodes, on_board_display } => {
+            let value = match (nodes, on_board_display) {
+                (DisplayNodes::Children, true) => 0,
+                (DisplayNodes::Siblings, true) => 1,
+                (DisplayNodes::Children, false) => 2,
+                (DisplayNodes::Siblings, false) => 3,
+            };
+            ("ST".to_string(), value.to_string())
+        }
+        SgfToken::Size(width, height) if width == height => ("SZ".to_string(), width.to_string()),
+        SgfToken::Size(width, height) => ("SZ".to_string(), format!("{}:{}", width, height)),
+        SgfToken::FileFormat(version) => ("FF".to_string(), version.to_string()),
+        SgfToken::TimeLimit(time) => ("TM".to_string(), time.to_string()),
+        SgfToken::Handicap(stones) => ("HA".to_string(), stones.to_string()),
+        // All text is decoded when read, so it is always written as UTF-8
+        SgfToken::Charset(_) => ("CA".to_string(), "UTF-8".to_string()),
+        SgfToken::Application { name, version } => (
+            "AP".to_string(),
+            format!("{}:{}", escape_composed_text(name), escape_composed_text(version)),
+        ),
+        SgfToken::Square { coordinate } => ("SQ".to_string(), point_to_string(*coordinate)),
+        SgfToken::Triangle { coordinate } => ("TR".to_string(), point_to_string(*coordinate)),
+        SgfToken::Label { label, coordinate } => (
+            "LB".to_string(),
+            format!("{}:{}", point_to_string(*coordinate), escape_composed_text(label)),
+        ),
+        SgfToken::Unknown((ident, value)) | SgfToken::Invalid((ident, value)) => (ident.clone(), value.clone()),
+    }
+}
+
+/// Parses a single SGF game tree into a `GameTree`
+pub fn parse(input: &str) -> Result<GameTree, BadukError> {
+    let mut parser = Parser::new(input);
+    parser.skip_to_game_tree().map_err(BadukError::invalid_input)?;
+    parser.parse_game_tree().map_err(BadukError::invalid_input)
+}
+
+/// Parses a single SGF game tree into a `GameTree` that only stores the board at every
+/// `interval` moves, see `GameTree::set_checkpoint_interval`. Uses much less memory for large
+/// files.
+pub fn parse_with_checkpoints(input: &str, interval: usize) -> Result<GameTree, BadukError> {
+    let mut parser = Parser::new(input);
+    parser.checkpoint_interval = Some(interval);
+    parser.skip_to_game_tree().map_err(BadukError::invalid_input)?;
+    parser.parse_game_tree().map_err(BadukError::invalid_input)
+}
+
+/// Parses a single SGF game tree from raw bytes, decoding text using the charset given by the
+/// `CA` property
+pub fn parse_bytes(input: &[u8]) -> Result<GameTree, BadukError> {
+    parse(&decode(input))
+}
+
+/// Parses every game tree in an SGF collection from raw bytes, decoding text using the charset
+/// given by the `CA` property
+pub fn parse_collection_bytes(input: &[u8]) -> Result<Vec<GameTree>, BadukError> {
+    parse_collection(&decode(input))
+}
+
+/// Decodes raw SGF data to a string.
+///
+/// Uses the charset from the first `CA` property found. Without one, the data is read as UTF-8 if
+/// it is valid UTF-8, and as Windows-1252 otherwise. That is a superset of ISO-8859-1, the SGF
+/// default, and is what `encoding_rs` uses for ISO-8859-1 labels as well.
+pub fn decode(input: &[u8]) -> String {
+    let encoding = find_charset(input).unwrap_or_else(|| match std::str::from_utf8(input) {
+        Ok(_) => encoding_rs::UTF_8,
+        Err(_) => encoding_rs::WINDOWS_1252,
+    });
+    let (text, _, _) = encoding.decode(input);
+    text.into_owned()
+}
+
+const SHIFT_JIS_LEAD_BYTES: &[RangeInclusive<u8>] = &[0x81..=0x9F, 0xE0..=0xFC];
+const DOUBLE_BYTE_LEAD_BYTES: &[RangeInclusive<u8>] = &[0x81..=0xFE];
+
+/// Gets the lead bytes of charsets where the second byte of a character can be a backslash
+fn lead_bytes(encoding: &'static encoding_rs::Encoding) -> &'static [RangeInclusive<u8>] {
+    if encoding == encoding_rs::SHIFT_JIS {
+        SHIFT_JIS_LEAD_BYTES
+    } else if encoding == encoding_rs::BIG5 || encoding == encoding_rs::GBK || encoding == encoding_rs::GB18030 {
+        DOUBLE_BYTE_LEAD_BYTES
+    } else {
+        &[]
+    }
+}
+
+/// Finds the charset given by the first `CA` property, without decoding the rest of the data.
+///
+/// Values before the `CA` property may hold multibyte characters ending in a backslash, so the
+/// data is scanned for each kind of charset, and a `CA` property naming the kind it was scanned
+/// as is preferred.
+fn find_charset(input: &[u8]) -> Option<&'static encoding_rs::Encoding> {
+    let mut found = None;
+    for lead_bytes in &[SHIFT_JIS_LEAD_BYTES, DOUBLE_BYTE_LEAD_BYTES, &[]] {
+        let mut scanner = ValueScanner::new(lead_bytes);
+        for c in input {
+            scanner.push(*c);
+            if scanner.charset.is_some() {
+                break;
+            }
+        }
+        match scanner.charset {
+            Some(encoding) if self::lead_bytes(encoding) == *lead_bytes => return Some(encoding),
+            Some(encoding) => found = found.or(Some(encoding)),
+            None => {}
+        }
+    }
+    found
+}
+
+/// Follows raw SGF data a byte at a time to tell property values from the rest, without decoding
+/// it. Once a `CA` property is read, the lead bytes of its charset are skipped over, so a second
+/// byte that looks like a backslash is not taken as an escape.
+struct ValueScanner {
+    lead_bytes: &'static [RangeInclusive<u8>],
+    ident: Vec<u8>,
+    in_ident: bool,
+    in_value: bool,
+    skip_next: bool,
+    value: Vec<u8>,
+    charset: Option<&'static encoding_rs::Encoding>,
+}
+
+impl ValueScanner {
+    fn new(lead_bytes: &'static [RangeInclusive<u8>]) -> ValueScanner {
+        ValueScanner {
+            lead_bytes,
+            ident: vec![],
+            in_ident: false,
+            in_value: false,
+            skip_next: false,
+            value: vec![],
+            charset: None,
+        }
+    }
+
+    /// Reads the next byte, returning whether it is part of a property value, brackets included
+    fn push(&mut self, c: u8) -> bool {
+        if !self.in_value {
+            match c {
+                b'[' => {
+                    self.in_value = true;
+                    self.value.clear();
+                    return true;
+                }
+                c if c.is_ascii_alphabetic() => {
+                    if !self.in_ident {
+                        self.ident.clear();
+                        self.in_ident = true;
+                    }
+                    if c.is_ascii_uppercase() {
+                        self.ident.push(c);
+                    }
+                }
+                c if c.is_ascii_whitespace() => self.in_ident = false,
+                _ => {
+                    self.ident.clear();
+                    self.in_ident = false;
+                }
+            }
+            return false;
+        }
+        if self.skip_next {
+            self.skip_next = false;
+        } else if c == b']' {
+            self.in_value = false;
+            self.in_ident = false;
+            if self.ident == b"CA" && self.charset.is_none() {
+                let label = String::from_utf8_lossy(&self.value);
+                self.charset = encoding_rs::Encoding::for_label(label.trim().as_bytes());
+                if let Some(encoding) = self.charset {
+                    self.lead_bytes = lead_bytes(encoding);
+                }
+            }
+            return true;
+        } else {
+            self.skip_next = c == b'\\' || self.lead_bytes.iter().any(|range| range.contains(&c));
+        }
+        if self.ident == b"CA" {
+            self.value.push(c);
+        }
+        true
+    }
+}
+
+/// Parses every game tree in an SGF collection, like `(;...)(;...)`
+pub fn parse_collection(input: &str) -> Result<Vec<GameTree>, BadukError> {
+    let mut parser = Parser::new(input);
+    parser.skip_to_game_tree().map_err(BadukError::invalid_input)?;
+    let mut games = vec![];
+    while !parser.is_at_end() {
+        games.push(parser.parse_game_tree().map_err(BadukError::invalid_input)?);
+    }
+    Ok(games)
+}
+
+/// Writes a collection of game trees, with each game on its own line
+pub fn write_collection(games: &[GameTree]) -> String {
+    games.iter().map(write).collect::<Vec<_>>().join("\n")
+}
+
+/// Reads the game trees of an SGF collection one at a time, so only a single game is held in
+/// memory while going through large collection files.
+///
+/// Each game is decoded on its own, using the charset from its `CA` property. Game trees are split
+/// up before decoding, so with charsets like Shift_JIS the `CA` property has to come before any
+/// text in that charset.
+pub struct CollectionReader<R: BufRead> {
+    reader: R,
+    buffer: Vec<u8>,
+    offset: usize,
+    checkpoint_interval: Option<usize>,
+}
+
+impl<R: BufRead> CollectionReader<R> {
+    pub fn new(reader: R) -> CollectionReader<R> {
+        CollectionReader {
+            reader,
+            buffer: vec![],
+            offset: 0,
+            checkpoint_interval: None,
+        }
+    }
+
+    /// Only stores the board at every `interval` moves in the games read, see
+    /// `GameTree::set_checkpoint_interval`
+    pub fn checkpoint_interval(mut self, interval: usize) -> Self {
+        self.checkpoint_interval = Some(interval);
+        self
+    }
+
+    /// Reads the raw bytes of the next game tree into the buffer, returning false at the end of
+    /// the input
+    fn read_game_tree(&mut self) -> Result<bool, BadukError> {
+        self.buffer.clear();
+        let mut depth = 0;
+        let mut scanner = ValueScanner::new(&[]);
+        loop {
+            let available = self.reader.fill_buf().map_err(BadukError::invalid_input)?;
+            if available.is_empty() {
+                if depth > 0 || scanner.in_value {
+                    return Err(BadukError::invalid_input(SgfError {
+                        message: "Unterminated game tree",
+                        offset: self.offset,
+                    }));
+                }
+                return Ok(false);
+            }
+            let mut used = 0;
+            let mut complete = false;
+            for c in available.iter() {
+                used += 1;
+                if depth == 0 && *c != b'(' {
+                    // Anything between game trees is ignored
+                    continue;
+                }
+                self.buffer.push(*c);
+                if !scanner.push(*c) {
+                    match c {
+                        b'(' => depth += 1,
+                        b')' => depth -= 1,
+                        _ => {}
+                    }
+                    if depth == 0 {
+                        complete = true;
+                        break;
+                    }
+                }
+            }
+            self.reader.consume(used);
+            self.offset += used;
+            if complete {
+                return Ok(true);
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for CollectionReader<R> {
+    type Item = Result<GameTree, BadukError>;
+
+    fn next(&mut self) -> Option<Result<GameTree, BadukError>> {
+        match self.read_game_tree() {
+            Ok(false) => None,
+            Ok(true) => {
+                let input = decode(&self.buffer);
+                let mut parser = Parser::new(&input);
+                parser.checkpoint_interval = self.checkpoint_interval;
+                Some(parser.parse_game_tree().map_err(BadukError::invalid_input))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// Writes a `GameTree`, including all variations, as an SGF game tree. The text is UTF-8, and the
+/// root always has a `CA[UTF-8]` property.
+pub fn write(game: &GameTree) -> String {
+    let mut out = String::new();
+    if !game.nodes.is_empty() {
+        write_variation(game, game.root, &mut out);
+    }
+    out
+}
+
+fn write_variation(game: &GameTree, mut index: GameTreeIndex, out: &mut String) {
+    out.push('(');
+    loop {
+        let tokens = &game.nodes[index].tokens;
+        if index == game.root && !tokens.iter().any(|token| matches!(token, SgfToken::Charset(_))) {
+            // Text is always written as UTF-8, so the root says so even without a `CA` property
+            let mut root = vec![SgfToken::Charset(Encoding::UTF8)];
+            root.extend(tokens.iter().cloned());
+            write_node(&root, out);
+        } else {
+            write_node(tokens, out);
+        }
+        let children = &game.nodes[index].children;
+        match children.len() {
+            0 => break,
+            1 => index = children[0],
+            _ => {
+                children.iter().for_each(|child| write_variation(game, *child, out));
+                break;
+            }
+        }
+    }
+    out.push(')');
+}
+
+fn write_node(tokens: &[SgfToken], out: &mut String) {
+    out.push(';');
+    // A property may only appear once in a node, so all values of an identifier are written
+    // together where it first appears
+    let mut properties: Vec<(String, Vec<String>)> = vec![];
+    for token in tokens {
+        let (ident, value) = token_to_property(token);
+        match properties.iter_mut().find(|(other, _)| *other == ident) {
+            Some((_, values)) => values.push(value),
+            None => properties.push((ident, vec![value])),
+        }
+    }
+    for (ident, values) in properties {
+        out.push_str(&ident);
+        for value in values {
+            out.push('[');
+            out.push_str(&value);
+            out.push(']');
+        }
+    }
+}
+
+pub(crate) struct Parser<'a> {
+    input: &'a str,
+    offset: usize,
+    checkpoint_interval: Option<usize>,
+}
+
+impl<'a> Parser<'a> {
+    pub(crate) fn new(input: &'a str) -> Parser<'a> {
+        Parser {
+            input,
+            offset: 0,
+            checkpoint_interval: None,
+        }
+    }
+
+    fn error(&self, message: &'static str) -> SgfError {
+        SgfError { message, offset: self.offset }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.offset).copied()
+    }
+
+    fn is_at_end(&mut self) -> bool {
+        self.skip_whitespace();
+        self.offset >= self.input.len()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if !c.is_ascii_whitespace() {
+                break;
+            }
+            self.offset += 1;
+        }
+    }
+
+    fn expect(&mut self, c: u8, message: &'static str) -> Result<(), SgfError> {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.offset += 1;
+            Ok(())
+        } else {
+            Err(self.error(message))
+        }
+    }
+
+    /// Skips anything before the start of the next game tree
+    pub(crate) fn skip_to_game_tree(&mut self) -> Result<(), SgfError> {
+        match self.input[self.offset..].find('(') {
+            Some(index) => {
+                self.offset += index;
+                Ok(())
+            }
+            None => Err(self.error("Missing game tree")),
+        }
+    }
+
+    pub(crate) fn parse_game_tree(&mut self) -> Result<GameTree, SgfError> {
+        let mut game = GameTree::default();
+        game.set_checkpoint_interval(self.checkpoint_interval);
+        self.parse_variation(&mut game, None)?;
+        // The last node of each variation still holds its board
+        game.release_states();
+        Ok(game)
+    }
+
+    fn parse_variation(&mut self, game: &mut GameTree, parent: Option<GameTreeIndex>) -> Result<(), SgfError> {
+        self.expect(b'(', "Expected '('")?;
+        self.skip_whitespace();
+        if self.peek() != Some(b';') {
+            return Err(self.error("Expected ';'"));
+        }
+        let mut current = parent;
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b';') {
+                break;
+            }
+            self.offset += 1;
+            let node = match current {
+                None => game.root,
+                Some(parent) => game.create_new_node(parent),
+            };
+            if current.is_none() && game.nodes[node].state.is_none() {
+                // Go games default to a 19x19 board when no size is given, and the root setup
+                // has to be placed on it
+                game.nodes[node].state = Some(GameState::default());
+            }
+            self.parse_properties(game, node)?;
+            current = Some(node);
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'(') {
+                break;
+            }
+            self.parse_variation(game, current)?;
+        }
+        self.expect(b')', "Expected ')'")
+    }
+
+    fn parse_properties(&mut self, game: &mut GameTree, node: GameTreeIndex) -> Result<(), SgfError> {
+        loop {
+            self.skip_whitespace();
+            let start = self.offset;
+            while let Some(c) = self.peek() {
+                if !c.is_ascii_alphabetic() {
+                    break;
+                }
+                self.offset += 1;
+            }
+            if start == self.offset {
+                return Ok(());
+            }
+            // Older file formats allow lower case letters in identifiers, like `AddBlack`. Those
+            // without any upper case letter are kept as they are, and end up as unknown tokens.
+            let raw = &self.input[start..self.offset];
+            let mut ident = raw.chars().filter(char::is_ascii_uppercase).collect::<String>();
+            if ident.is_empty() {
+                ident = raw.to_string();
+            }
+            self.skip_whitespace();
+            if self.peek() != Some(b'[') {
+                return Err(self.error("Expected property value"));
+            }
+            while self.peek() == Some(b'[') {
+                let value = self.parse_value()?;
+                read_property(game, node, &ident, value);
+                self.skip_whitespace();
+            }
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<&'a str, SgfError> {
+        self.offset += 1;
+        let start = self.offset;
+        let bytes = self.input.as_bytes();
+        while self.offset < bytes.len() {
+            match bytes[self.offset] {
+                b'\\' => self.offset += 2,
+                b']' => {
+                    let value = &self.input[start..self.offset];
+                    self.offset += 1;
+                    return Ok(value);
+                }
+                _ => self.offset += 1,
+            }
+        }
+        Err(self.error("Unterminated property value"))
     }
 }
+
+/// Adds a property to a node, applying it to the board where it changes it
+pub(crate) fn read_property(game: &mut GameTree, node: GameTreeIndex, identifier: &str, value: &str) {
+    for token in parse_property_value(identifier, value) {
+        if game.parse_sgf_token(&token, node).is_err() {
+            game.add_token(node, &token);
+        }
+    }
+}
+
+fn is_valid_value(value_type: ValueType, value: &str) -> bool {
+    match value_type {
+        ValueType::None => value.is_empty(),
+        ValueType::Number => value.trim().parse::<i64>().is_ok(),
+        ValueType::Real => value.trim().parse::<f64>().is_ok(),
+        ValueType::Double => value == "1" || value == "2",
+        ValueType::Color => value == "B" || value == "W",
+        ValueType::Move => parse_move(value).is_some(),
+        ValueType::PointList => parse_point_list(value).is_some(),
+        ValueType::EmptyPointList => value.is_empty() || parse_point_list(value).is_some(),
+        ValueType::PointPairList => match split_composed(value) {
+            Some((from, to)) => parse_point(from).is_some() && parse_point(to).is_some(),
+            None => false,
+        },
+        ValueType::Label => match split_composed(value) {
+            Some((point, _)) => parse_point(point).is_some(),
+            None => false,
+        },
+        ValueType::Figure => match split_composed(value) {
+            Some((flags, _)) => flags.trim().parse::<i64>().is_ok(),
+            None => value.is_empty(),
+        },
+        ValueType::SimpleText | ValueType::Text | ValueType::Application | ValueType::Size => true,
+    }
+}
+
+fn parse_move(value: &str) -> Option<Action> {
+    if value.is_empty() {
+        Some(Action::Pass)
+    } else {
+        parse_point(value).map(|(x, y)| Action::Move(x, y))
+    }
+}
+
+/// Parses a single SGF point, like `aa` or `sS`
+pub(crate) fn parse_point(value: &str) -> Option<(u8, u8)> {
+    let bytes = value.as_bytes();
+    if bytes.len() != 2 {
+        return None;
+    }
+    let convert = |c: u8| match c {
+        b'a'..=b'z' => Some(c - b'a' + 1),
+        b'A'..=b'Z' => Some(c - b'A' + 27),
+        _ => None,
+    };
+    Some((convert(bytes[0])?, convert(bytes[1])?))
+}
+
+/// Parses a point, or a compressed rectangle of points like `aa:cc`
+pub(crate) fn parse_point_list(value: &str) -> Option<Vec<(u8, u8)>> {
+    match split_composed(value) {
+        None => parse_point(value).map(|point| vec![point]),
+        Some((from, to)) => {
+            let (x1, y1) = parse_point(from)?;
+            let (x2, y2) = parse_point(to)?;
+            let mut points = vec![];
+            for y in y1.min(y2)..=y1.max(y2) {
+                for x in x1.min(x2)..=x1.max(x2) {
+                    points.push((x, y));
+                }
+            }
+            Some(points)
+        }
+    }
+}
+
+/// Writes a point as two SGF letters, or `None` if a coordinate is outside 1 to 52
+pub(crate) fn point_to_string(point: impl Into<Position>) -> Option<String> {
+    let point = point.into();
+    let mut out = String::with_capacity(2);
+    out.push(coordinate_to_char(point.x())?);
+    out.push(coordinate_to_char(point.y())?);
+    Some(out)
+}
+
+/// Gets the SGF letter for a single coordinate, `a` to `z` followed by `A` to `Z`
+pub(crate) fn coordinate_to_char(c: u32) -> Option<char> {
+    match c {
+        1..=26 => std::char::from_u32('a' as u32 + c - 1),
+        27..=52 => std::char::from_u32('A' as u32 + c - 27),
+        _ => None,
+    }
+}
+
+/// Splits a composed value at the first unescaped ':'
+pub(crate) fn split_composed(value: &str) -> Option<(&str, &str)> {
+    let bytes = value.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' => index += 2,
+            b':' => return Some((&value[..index], &value[index + 1..])),
+            _ => index += 1,
+        }
+    }
+    None
+}
+
+/// Removes SGF escaping from a text value, including escaped line breaks
+pub(crate) fn unescape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match (chars.next(), chars.peek()) {
+            (Some('\n'), Some('\r')) | (Some('\r'), Some('\n')) => {
+                chars.next();
+            }
+            (Some('\n'), _) | (Some('\r'), _) | (None, _) => {}
+            (Some(c), _) => out.push(c),
+        }
+    }
+    out
+}
+
+/// Escapes a text value so it can be written between brackets
+pub(crate) fn escape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        if c == ']' || c == '\\' {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Escapes a text value that is part of a composed value, where ':' is a separator
+pub(crate) fn escape_composed_text(value: &str) -> String {
+    escape_text(value).replace(':', "\\:")
+}
@@ -1,14 +1,17 @@
-use crate::{BadukError, BadukErrorKind, Position, Color};
-use std::collections::HashSet;
+use crate::{BadukError, BadukErrorKind, Coordinates, Position, Color};
+use crate::sgf;
+use crate::bitboard::BitBoard;
 
 type Intersection = Option<Color>;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Captures {
     pub white: i32,
     pub black: i32
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for Captures {
     fn default() -> Captures {
         Captures {
@@ -39,24 +42,38 @@ impl GameStateDifference {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct GameState {
-    pub board: Vec<Intersection>,
+    black: BitBoard,
+    white: BitBoard,
     pub captures: Captures,
     pub width: u32,
     pub height: u32,
+    /// The color to play next, if known
+    pub to_move: Option<Color>,
+    /// The point that can not be played right away because it would retake a ko
+    pub ko: Option<Position>,
+    pub komi: Option<f32>,
 }
 
 impl GameState {
+    /// The largest board width or height that SGF coordinates can describe
+    pub const MAX_SIZE: u32 = 52;
+
     pub fn new(width: u32, height: u32) -> GameState {
         GameState {
-            board: vec![None; (width * height) as usize],
+            black: BitBoard::new(width, height),
+            white: BitBoard::new(width, height),
             captures: Captures::default(),
             width,
             height,
+            to_move: None,
+            ko: None,
+            komi: None,
         }
     }
 
+    #[allow(clippy::result_unit_err)]
     pub fn difference(&self, other: &GameState) -> Result<GameStateDifference, ()> {
         if self.width != other.width || self.height != other.height {
             return Err(());
@@ -90,8 +107,36 @@ impl GameState {
         (self.width, self.height)
     }
 
+    /// Gets the coordinate conversions for the size of this board
+    pub fn coordinates(&self) -> Coordinates {
+        Coordinates::new(self.width, self.height)
+    }
+
     pub fn is_empty(&self) -> bool {
-        self.board.iter().all(Option::is_none)
+        self.black.is_empty() && self.white.is_empty()
+    }
+
+    /// Gets the stones of a color as a bit board
+    pub fn stones(&self, color: Color) -> &BitBoard {
+        match color {
+            Color::Black => &self.black,
+            Color::White => &self.white,
+        }
+    }
+
+    /// Gets all empty points as a bit board
+    pub fn empty_points(&self) -> BitBoard {
+        self.black.or(&self.white).not()
+    }
+
+    /// Gets every point of the board, row by row from the top left, so the point at `x`, `y` is at
+    /// index `(y - 1) * width + (x - 1)`. This is the layout of the `board` field earlier versions
+    /// had.
+    pub fn board(&self) -> Vec<Intersection> {
+        (1..=self.height)
+            .flat_map(|y| (1..=self.width).map(move |x| (x, y)))
+            .map(|pos| self.get_stone(pos).copied())
+            .collect()
     }
 
     pub fn is_valid_position(&self, pos: impl Into<Position>) -> bool {
@@ -107,7 +152,13 @@ impl GameState {
             return None;
         }
         let index = self.position_to_index(pos);
-        self.board[index].as_ref()
+        if self.black.get(index) {
+            Some(&Color::Black)
+        } else if self.white.get(index) {
+            Some(&Color::White)
+        } else {
+            None
+        }
     }
 
     pub fn place_stone(&self, pos: impl Into<Position>, color: Color) -> Result<GameState, BadukError> {
@@ -115,22 +166,25 @@ impl GameState {
         if !self.is_valid_position(pos) {
             return Err(BadukErrorKind::InvalidPosition(pos).into());
         }
+        if self.get_stone(pos).is_some() {
+            return Err(BadukErrorKind::AlreadyOccupied(pos).into());
+        }
         let index = self.position_to_index(pos);
         let mut state = (*self).clone();
-        match state.board[index] {
-            None => state.board[index] = Some(color),
-            Some(_) => {
-                return Err(BadukErrorKind::AlreadyOccupied(pos).into());
-            }
-        }
+        state.stones_mut(color).set(index);
         Ok(state)
     }
 
+    /// Puts a stone on the board without checking for captures, replacing any stone already there
     pub fn add_stone(&self, pos: impl Into<Position>, color: Color) -> Result<GameState, BadukError> {
         let pos = pos.into();
+        if !self.is_valid_position(pos) {
+            return Err(BadukErrorKind::InvalidPosition(pos).into());
+        }
         let index = self.position_to_index(pos);
         let mut state = (*self).clone();
-        state.board[index] = Some(color);
+        state.stones_mut(!color).clear(index);
+        state.stones_mut(color).set(index);
         Ok(state)
     }
 
@@ -140,88 +194,68 @@ impl GameState {
             return Err(BadukErrorKind::InvalidPosition(pos).into());
         }
         let index = self.position_to_index(pos);
-        self.board[index] = None;
+        self.black.clear(index);
+        self.white.clear(index);
         Ok(())
     }
 
+    /// Updates the side to move and the ko point after a move at `pos` captured the given stones
+    pub(crate) fn record_move(&mut self, pos: Position, color: Color, captured: &[Position]) {
+        self.to_move = Some(!color);
+        let is_single_stone = self.get_chain(pos).map(|chain| chain.count() == 1).unwrap_or(false);
+        self.ko = match captured {
+            [ko] if is_single_stone && self.count_liberties(pos) == Some(1) => Some(*ko),
+            _ => None,
+        };
+    }
+
     pub fn count_liberties(&self, pos: impl Into<Position>) -> Option<u32> {
         let pos = pos.into();
         if !self.is_valid_position(pos) {
             return None;
         }
-        Some(self.get_chain(pos)?.iter().fold(0, |acc, pos| {
-            acc + self.get_neighbours(*pos).iter().fold(0, |acc, pos| {
-                if self.get_stone(*pos) == None {
-                    acc + 1
-                } else {
-                    acc
-                }
-            })
-        }))
+        let chain = self.get_chain(pos)?;
+        Some(chain.neighbours().and(&self.empty_points()).count())
     }
 
     // TODO: According to SGF spec, should only remove dead stones affected by last move
     pub fn remove_dead_stones(&mut self, color: Color) -> Vec<Position> {
-        let mut dead_stones = vec![];
-        for x in 1..=self.width {
-            for y in 1..=self.height {
-                if self.get_stone((x, y)) != Some(&color) {
-                    continue;
-                }
-                if let Some(chain) = self.get_chain((x, y)) {
-                    let liberties = chain.iter().fold(0, |acc, pos| {
-                        acc + self.get_neighbours(*pos).iter().fold(0, |acc, pos| {
-                            if self.get_stone(*pos) == None {
-                                acc + 1
-                            } else {
-                                acc
-                            }
-                        })
-                    });
-                    if liberties == 0 {
-                        chain.into_iter().for_each(|pos| {
-                            dead_stones.push(pos);
-                            let _ = self.remove_stone(pos);
-                        });
-                    }
-                }
-            }
-        }
-        dead_stones
+        let dead = self.dead_stones(color);
+        let positions = dead.indices().map(|index| self.index_to_position(index)).collect();
+        let stones = self.stones(color).and_not(&dead);
+        *self.stones_mut(color) = stones;
+        positions
     }
 
     pub fn is_valid(&self) -> bool {
-        for x in 1..=self.width {
-            for y in 1..=self.height {
-                if self.count_liberties((x, y)) == Some(0) {
-                    return false;
-                }
-            }
-        }
-        true
+        self.dead_stones(Color::Black).is_empty() && self.dead_stones(Color::White).is_empty()
     }
 
-    fn get_chain(&self, pos: impl Into<Position>) -> Option<Vec<Position>> {
+    /// Gets the stones of a color in chains without any liberties
+    fn dead_stones(&self, color: Color) -> BitBoard {
+        let stones = self.stones(color);
+        let alive = self.empty_points().neighbours().and(stones).flood(stones);
+        stones.and_not(&alive)
+    }
+
+    fn get_chain(&self, pos: impl Into<Position>) -> Option<BitBoard> {
         let pos = pos.into();
-        let stone = self.get_stone(pos)?;
-        let mut tried: HashSet<Position> = HashSet::default();
-        let mut chain = vec![pos];
-        let mut pool = vec![pos];
-
-        tried.insert(pos);
-        while let Some(pos) = pool.pop() {
-            self.get_neighbours(pos).iter().for_each(|n| {
-                match tried.get(n) {
-                    None if self.get_stone(*n) == Some(stone) => {
-                        chain.push(*n);
-                        pool.push(*n);
-                    }
-                    _ => {}
-                }
-                tried.insert(*n);
-            });
+        let stones = self.stones(*self.get_stone(pos)?);
+        let mut seed = BitBoard::new(self.width, self.height);
+        seed.set(self.position_to_index(pos));
+        Some(seed.flood(stones))
+    }
+
+    fn stones_mut(&mut self, color: Color) -> &mut BitBoard {
+        match color {
+            Color::Black => &mut self.black,
+            Color::White => &mut self.white,
         }
-        Some(chain)
+    }
+
+    pub(crate) fn index_to_position(&self, index: usize) -> Position {
+        let index = index as u32;
+        (index % self.width + 1, index / self.width + 1).into()
     }
 
     #[inline(always)]
@@ -229,82 +263,191 @@ impl GameState {
         let pos = pos.into();
         ((pos.x() - 1) + ((pos.y() - 1) * self.width)) as usize
     }
+}
 
-    fn get_neighbours(&self, pos: impl Into<Position>) -> Vec<Position> {
-        let pos = pos.into();
-        let mut neighbours = vec![];
-        if pos.x() > 1 {
-            neighbours.push((pos.x() - 1, pos.y()).into());
+impl Default for GameState {
+    fn default() -> GameState {
+        GameState::new(19, 19)
+    }
+}
+
+/// Parses a board in the text format written by `Display`.
+///
+/// The board is drawn with `.` for empty points, `x` for black and `o` for white stones, one line
+/// per row. Other characters on those lines are ignored. A board on a single line is taken to be
+/// square, unless a size is given. Lines containing a `:` are headers describing the rest of the
+/// state, all of them optional:
+///
+/// ```text
+/// size: 9x7
+/// to-move: white
+/// captures: black 2, white 0
+/// ko: cd
+/// komi: 6.5
+/// ```
+///
+/// The size is given as `WIDTHxHEIGHT`, or a single number for square boards. Captures count the
+/// stones of each color taken off the board, and the ko point uses SGF coordinates.
+impl std::str::FromStr for GameState {
+    type Err = BadukError;
+
+    fn from_str(data: &str) -> Result<GameState, BadukError> {
+        let mut header = GameState::new(0, 0);
+        let mut size = None;
+        let mut rows: Vec<Vec<Intersection>> = vec![];
+        for line in data.lines() {
+            if let Some((key, value)) = line.split_once(':') {
+                match key.trim() {
+                    "size" => size = Some(parse_size(value)?),
+                    key => parse_header(&mut header, key, value.trim())?,
+                }
+                continue;
+            }
+            let row: Vec<Intersection> = line
+                .chars()
+                .filter_map(|c| match c {
+                    '.' => Some(None),
+                    'x' => Some(Some(Color::Black)),
+                    'o' => Some(Some(Color::White)),
+                    _ => None,
+                })
+                .collect();
+            if !row.is_empty() {
+                rows.push(row);
+            }
         }
-        if pos.x() < self.width {
-            neighbours.push((pos.x() + 1, pos.y()).into());
+        let (width, height) = match (size, rows.len()) {
+            (_, 0) => return Err(BadukErrorKind::InvalidInputSize.into()),
+            (Some(size), 1) => size,
+            (None, 1) => {
+                let size = (rows[0].len() as f64).sqrt() as u32;
+                (size, size)
+            }
+            (_, height) => (rows[0].len() as u32, height as u32),
+        };
+        if rows.len() > 1 && rows.iter().any(|row| row.len() as u32 != width) {
+            return Err(BadukErrorKind::InvalidInputSize.into());
         }
-        if pos.y() > 1 {
-            neighbours.push((pos.x(), pos.y() - 1).into());
+        // The size is checked first, so a large `size:` header can not overflow the area
+        if width > GameState::MAX_SIZE
+            || height > GameState::MAX_SIZE
+            || size.map(|size| size != (width, height)).unwrap_or(false)
+            || width * height != rows.iter().map(Vec::len).sum::<usize>() as u32
+        {
+            return Err(BadukErrorKind::InvalidInputSize.into());
         }
-        if pos.y() < self.height {
-            neighbours.push((pos.x(), pos.y() + 1).into());
+        let mut state = GameState {
+            captures: header.captures,
+            to_move: header.to_move,
+            ko: header.ko,
+            komi: header.komi,
+            ..GameState::new(width, height)
+        };
+        for (index, intersection) in rows.into_iter().flatten().enumerate() {
+            if let Some(color) = intersection {
+                state.stones_mut(color).set(index);
+            }
+        }
+        if let Some(ko) = state.ko {
+            if !state.is_valid_position(ko) {
+                return Err(BadukErrorKind::InvalidPosition(ko).into());
+            }
         }
-        neighbours
+        Ok(state)
     }
 }
 
-impl Default for GameState {
-    fn default() -> GameState {
-        GameState {
-            board: vec![None; 361],
-            captures: Captures::default(),
-            width: 19,
-            height: 19,
-        }
+fn parse_size(value: &str) -> Result<(u32, u32), BadukError> {
+    let parse = |n: &str| n.trim().parse::<u32>().map_err(BadukError::invalid_input);
+    match value.split_once('x') {
+        Some((width, height)) => Ok((parse(width)?, parse(height)?)),
+        None => parse(value).map(|size| (size, size)),
     }
 }
 
-impl std::str::FromStr for GameState {
-    type Err = BadukError;
+fn parse_color(value: &str) -> Result<Color, BadukError> {
+    match value {
+        "black" | "B" => Ok(Color::Black),
+        "white" | "W" => Ok(Color::White),
+        _ => Err(BadukErrorKind::InvalidInput.into()),
+    }
+}
 
-    fn from_str(data: &str) -> Result<GameState, BadukError> {
-        let board = data.chars().fold(vec![], |mut board, c| {
-            match c {
-                '.' => board.push(None),
-                'x' => board.push(Some(Color::Black)),
-                'o' => board.push(Some(Color::White)),
-                _ => {}
+fn parse_header(state: &mut GameState, key: &str, value: &str) -> Result<(), BadukError> {
+    match key {
+        "to-move" => state.to_move = Some(parse_color(value)?),
+        "captures" => {
+            for part in value.split(',') {
+                let mut words = part.split_whitespace();
+                let color = parse_color(words.next().unwrap_or_default())?;
+                let count = words
+                    .next()
+                    .ok_or_else(|| BadukError::from(BadukErrorKind::InvalidInput))?
+                    .parse()
+                    .map_err(BadukError::invalid_input)?;
+                match color {
+                    Color::Black => state.captures.black = count,
+                    Color::White => state.captures.white = count,
+                }
             }
-            board
-        });
-        let size = (board.len() as f64).sqrt() as u32;
-        if size * size != (board.len() as u32) {
-            Err(BadukErrorKind::InvalidInputSize.into())
-        } else {
-            Ok(GameState {
-                board,
-                captures: Captures::default(),
-                width: size,
-                height: size,
-            })
         }
+        "ko" => {
+            let point = sgf::parse_point(value).ok_or_else(|| BadukError::from(BadukErrorKind::InvalidInput))?;
+            state.ko = Some(point.into());
+        }
+        "komi" => state.komi = Some(value.parse().map_err(BadukError::invalid_input)?),
+        _ => return Err(BadukErrorKind::InvalidInput.into()),
     }
+    Ok(())
 }
 
+/// Writes the full state, headers included, in the text format read by `FromStr`
+impl std::fmt::Display for GameState {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let color = |color: &Color| match color {
+            Color::Black => "black",
+            Color::White => "white",
+        };
+        writeln!(f, "size: {}x{}", self.width, self.height)?;
+        if let Some(ref to_move) = self.to_move {
+            writeln!(f, "to-move: {}", color(to_move))?;
+        }
+        writeln!(f, "captures: black {}, white {}", self.captures.black, self.captures.white)?;
+        if let Some(ko) = self.ko {
+            writeln!(f, "ko: {}", sgf::point_to_string(ko).unwrap_or_else(|| ko.to_string()))?;
+        }
+        if let Some(komi) = self.komi {
+            writeln!(f, "komi: {}", komi)?;
+        }
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Prints the board in the format read by `FromStr`. The alternate form, `{:#?}`, adds SGF
+/// coordinate labels along the top and left side.
 impl std::fmt::Debug for GameState {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let out =
-            self.board
-                .iter()
-                .enumerate()
-                .fold(String::new(), |mut out, (idx, intersection)| {
-                    if idx > 0 && (idx as u32) % self.width == 0 {
-                        out.push_str("\n");
-                    }
-                    let sym = match intersection {
-                        None => ".".to_string(),
-                        Some(Color::White) => "o".to_string(),
-                        Some(Color::Black) => "x".to_string(),
-                    };
-                    out.push_str(&sym);
-                    out
-                });
-        write!(f, "{}", out)
+        let label = |c: u32| sgf::coordinate_to_char(c).unwrap_or('?');
+        if f.alternate() {
+            let header: String = (1..=self.width).map(label).collect();
+            write!(f, "  {}", header)?;
+        }
+        for y in 1..=self.height {
+            if y > 1 || f.alternate() {
+                writeln!(f)?;
+            }
+            if f.alternate() {
+                write!(f, "{} ", label(y))?;
+            }
+            let row: String = (1..=self.width)
+                .map(|x| match self.get_stone((x, y)) {
+                    None => '.',
+                    Some(Color::White) => 'o',
+                    Some(Color::Black) => 'x',
+                })
+                .collect();
+            write!(f, "{}", row)?;
+        }
+        Ok(())
     }
 }
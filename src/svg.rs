@@ -0,0 +1,331 @@
+use crate::{GameState, GameTree, BadukError, BadukErrorKind, Color, Markup, Position, star_points};
+use crate::game::GameTreeIndex;
+use crate::position::column_label;
+use crate::render::row_label;
+use std::borrow::Cow;
+use std::fmt::{self, Write};
+use std::ops::RangeInclusive;
+
+const BOARD_COLOR: &str = "#dcb35c";
+
+/// Renders a board as an SVG image, with grid, star points, stones and optionally coordinates,
+/// move numbers and markup
+#[derive(Debug, Clone)]
+pub struct SvgRenderer<'a> {
+    state: Cow<'a, GameState>,
+    cell_size: u32,
+    coordinates: bool,
+    move_numbers: Vec<(Position, u32)>,
+    markup: Vec<Markup>,
+    caption: Vec<String>,
+}
+
+impl<'a> SvgRenderer<'a> {
+    pub fn new(state: &'a GameState) -> SvgRenderer<'a> {
+        SvgRenderer::with_state(Cow::Borrowed(state))
+    }
+
+    fn with_state(state: Cow<'a, GameState>) -> SvgRenderer<'a> {
+        SvgRenderer {
+            state,
+            cell_size: 24,
+            coordinates: true,
+            move_numbers: vec![],
+            markup: vec![],
+            caption: vec![],
+        }
+    }
+
+    /// Sets the distance between two lines of the grid, in pixels
+    pub fn cell_size(mut self, cell_size: u32) -> Self {
+        self.cell_size = cell_size.max(2);
+        self
+    }
+
+    pub fn coordinates(mut self, coordinates: bool) -> Self {
+        self.coordinates = coordinates;
+        self
+    }
+
+    /// Sets the numbers drawn on stones. Numbers on points without a stone are left out.
+    pub fn move_numbers(mut self, move_numbers: Vec<(Position, u32)>) -> Self {
+        self.move_numbers = move_numbers;
+        self
+    }
+
+    pub fn markup(mut self, markup: Vec<Markup>) -> Self {
+        self.markup = markup;
+        self
+    }
+
+    /// Sets lines of text drawn below the board
+    pub fn caption(mut self, caption: Vec<String>) -> Self {
+        self.caption = caption;
+        self
+    }
+
+    fn offset(&self) -> u32 {
+        if self.coordinates {
+            self.cell_size * 3 / 2
+        } else {
+            self.cell_size / 2
+        }
+    }
+
+    /// Gets the pixel coordinates for the center of a point
+    fn point(&self, pos: Position) -> (u32, u32) {
+        let offset = self.offset();
+        (offset + (pos.x() - 1) * self.cell_size, offset + (pos.y() - 1) * self.cell_size)
+    }
+
+    /// Gets the color used for drawing on top of a point, so it stands out against the stone
+    fn ink(&self, pos: Position) -> &'static str {
+        match self.state.get_stone(pos) {
+            Some(Color::Black) => "#fff",
+            _ => "#000",
+        }
+    }
+
+    fn write_grid(&self, out: &mut String) -> fmt::Result {
+        let (width, height) = self.state.dimensions();
+        let (left, top) = self.point((1, 1).into());
+        let (right, bottom) = self.point((width, height).into());
+        let mut path = String::new();
+        for x in 1..=width {
+            let (px, _) = self.point((x, 1).into());
+            write!(path, "M{} {}V{}", px, top, bottom)?;
+        }
+        for y in 1..=height {
+            let (_, py) = self.point((1, y).into());
+            write!(path, "M{} {}H{}", left, py, right)?;
+        }
+        writeln!(out, r##"<path d="{}" stroke="#000" stroke-width="1"/>"##, path)?;
+        let radius = self.cell_size as f64 / 10.0;
+        for pos in star_points(width, height) {
+            let (x, y) = self.point(pos);
+            writeln!(out, r##"<circle cx="{}" cy="{}" r="{}" fill="#000"/>"##, x, y, round(radius))?;
+        }
+        Ok(())
+    }
+
+    fn write_coordinates(&self, out: &mut String) -> fmt::Result {
+        let (width, height) = self.state.dimensions();
+        let font_size = self.cell_size as f64 * 0.45;
+        let near = self.cell_size / 2;
+        let (far_x, far_y) = self.point((width, height).into());
+        let (far_x, far_y) = (far_x + self.cell_size, far_y + self.cell_size);
+        for x in 1..=width {
+            let (px, _) = self.point((x, 1).into());
+            for py in [near, far_y] {
+                write_text(out, px, py, font_size, "#000", &column_label(x).unwrap_or_default())?;
+            }
+        }
+        for y in 1..=height {
+            let (_, py) = self.point((1, y).into());
+            for px in [near, far_x] {
+                write_text(out, px, py, font_size, "#000", &row_label(y, height))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_stones(&self, out: &mut String) -> fmt::Result {
+        let (width, height) = self.state.dimensions();
+        let radius = self.cell_size as f64 * 0.48;
+        for y in 1..=height {
+            for x in 1..=width {
+                let fill = match self.state.get_stone((x, y)) {
+                    Some(Color::Black) => "#000",
+                    Some(Color::White) => "#fff",
+                    None => continue,
+                };
+                let (px, py) = self.point((x, y).into());
+                writeln!(
+                    out,
+                    r##"<circle cx="{}" cy="{}" r="{}" fill="{}" stroke="#000" stroke-width="1"/>"##,
+                    px, py, round(radius), fill
+                )?;
+            }
+        }
+        let font_size = self.cell_size as f64 * 0.5;
+        for (pos, number) in &self.move_numbers {
+            if self.state.get_stone(*pos).is_some() {
+                let (px, py) = self.point(*pos);
+                write_text(out, px, py, font_size, self.ink(*pos), &number.to_string())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_markup(&self, out: &mut String) -> fmt::Result {
+        let size = self.cell_size as f64;
+        let has_arrows = self.markup.iter().any(|markup| matches!(markup, Markup::Arrow(..)));
+        if has_arrows {
+            writeln!(
+                out,
+                r##"<defs><marker id="arrowhead" viewBox="0 0 10 10" refX="9" refY="5" markerWidth="6" markerHeight="6" orient="auto"><path d="M0 0L10 5L0 10z" fill="#000"/></marker></defs>"##
+            )?;
+        }
+        for markup in &self.markup {
+            match markup {
+                Markup::Circle(pos) => {
+                    let (x, y) = self.point(*pos);
+                    writeln!(
+                        out,
+                        r#"<circle cx="{}" cy="{}" r="{}" fill="none" stroke="{}" stroke-width="2"/>"#,
+                        x, y, round(size * 0.25), self.ink(*pos)
+                    )?;
+                }
+                Markup::Square(pos) => {
+                    let (x, y) = self.point(*pos);
+                    let half = size * 0.22;
+                    writeln!(
+                        out,
+                        r#"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="{}" stroke-width="2"/>"#,
+                        round(x as f64 - half), round(y as f64 - half), round(half * 2.0), round(half * 2.0), self.ink(*pos)
+                    )?;
+                }
+                Markup::Triangle(pos) => {
+                    let (x, y) = self.point(*pos);
+                    let (x, y, r) = (x as f64, y as f64, size * 0.28);
+                    writeln!(
+                        out,
+                        r#"<polygon points="{},{} {},{} {},{}" fill="none" stroke="{}" stroke-width="2"/>"#,
+                        round(x), round(y - r), round(x - r * 0.87), round(y + r * 0.5), round(x + r * 0.87), round(y + r * 0.5), self.ink(*pos)
+                    )?;
+                }
+                Markup::Cross(pos) => {
+                    let (x, y) = self.point(*pos);
+                    let (x, y, r) = (x as f64, y as f64, size * 0.2);
+                    writeln!(
+                        out,
+                        r#"<path d="M{} {}L{} {}M{} {}L{} {}" stroke="{}" stroke-width="2"/>"#,
+                        round(x - r), round(y - r), round(x + r), round(y + r), round(x - r), round(y + r), round(x + r), round(y - r), self.ink(*pos)
+                    )?;
+                }
+                Markup::Label(pos, label) => {
+                    let (x, y) = self.point(*pos);
+                    if self.state.get_stone(*pos).is_none() {
+                        // Hide the grid behind the label
+                        writeln!(
+                            out,
+                            r#"<circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+                            x, y, round(size * 0.4), BOARD_COLOR
+                        )?;
+                    }
+                    write_text(out, x, y, size * 0.5, self.ink(*pos), label)?;
+                }
+                Markup::Arrow(from, to) | Markup::Line(from, to) => {
+                    let (x1, y1) = self.point(*from);
+                    let (x2, y2) = self.point(*to);
+                    let marker = if has_arrows && matches!(markup, Markup::Arrow(..)) {
+                        r#" marker-end="url(#arrowhead)""#
+                    } else {
+                        ""
+                    };
+                    writeln!(
+                        out,
+                        r##"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="#000" stroke-width="2"{}/>"##,
+                        x1, y1, x2, y2, marker
+                    )?;
+                }
+                Markup::Dimmed(pos) => {
+                    let (x, y) = self.point(*pos);
+                    let half = size / 2.0;
+                    writeln!(
+                        out,
+                        r##"<rect x="{}" y="{}" width="{}" height="{}" fill="#fff" fill-opacity="0.5"/>"##,
+                        round(x as f64 - half), round(y as f64 - half), size, size
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_text(out: &mut String, x: u32, y: u32, font_size: f64, color: &str, text: &str) -> fmt::Result {
+    writeln!(
+        out,
+        r#"<text x="{}" y="{}" font-family="sans-serif" font-size="{}" fill="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+        x, y, round(font_size), color, escape_xml(text)
+    )
+}
+
+/// Rounds a length to two decimals, to keep the output short
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl<'a> fmt::Display for SvgRenderer<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (width, height) = self.state.dimensions();
+        let image_width = self.offset() * 2 + width.saturating_sub(1) * self.cell_size;
+        let board_height = self.offset() * 2 + height.saturating_sub(1) * self.cell_size;
+        let image_height = board_height + self.caption.len() as u32 * self.cell_size;
+        let mut out = String::new();
+        writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+            image_width, image_height
+        )?;
+        writeln!(out, r#"<rect width="{}" height="{}" fill="{}"/>"#, image_width, image_height, BOARD_COLOR)?;
+        self.write_grid(&mut out)?;
+        if self.coordinates {
+            self.write_coordinates(&mut out)?;
+        }
+        self.write_stones(&mut out)?;
+        self.write_markup(&mut out)?;
+        for (line, text) in self.caption.iter().enumerate() {
+            writeln!(
+                out,
+                r##"<text x="{}" y="{}" font-family="sans-serif" font-size="{}" fill="#000" dominant-baseline="central">{}</text>"##,
+                self.cell_size / 2,
+                board_height + line as u32 * self.cell_size + self.cell_size / 2,
+                round(self.cell_size as f64 * 0.5),
+                escape_xml(text)
+            )?;
+        }
+        writeln!(out, "</svg>")?;
+        write!(f, "{}", out)
+    }
+}
+
+impl GameState {
+    /// Gets a renderer for drawing the board as an SVG image, see `SvgRenderer`
+    pub fn svg(&self) -> SvgRenderer<'_> {
+        SvgRenderer::new(self)
+    }
+}
+
+impl GameTree {
+    /// Gets a renderer for drawing the board of a node as an SVG image, including the markup of
+    /// the node
+    pub fn svg(&self, node: GameTreeIndex) -> Result<SvgRenderer<'_>, BadukError> {
+        match self.state(node) {
+            None => Err(BadukErrorKind::MissingGoBoard.into()),
+            Some(state) => Ok(SvgRenderer::with_state(state).markup(self.get_markup(node))),
+        }
+    }
+
+    /// Gets the positions and numbers of the moves leading up to a node that fall in a range of
+    /// move numbers. Moves on a point that was played again later are left out.
+    pub fn move_numbers(&self, node: GameTreeIndex, moves: RangeInclusive<u32>) -> Vec<(Position, u32)> {
+        let mut numbers: Vec<(Position, u32)> = vec![];
+        for (number, _, pos) in self.numbered_moves(node) {
+            if let Some(pos) = pos {
+                numbers.retain(|(p, _)| *p != pos);
+                numbers.push((pos, number));
+            }
+        }
+        numbers.retain(|(_, number)| moves.contains(number));
+        numbers
+    }
+}
@@ -0,0 +1,203 @@
+//! Reading and writing UGF files, as used by Japanese servers.
+//!
+//! The `[Header]` section holds `key=value` lines, like `PlayerB=Iyama Yuta,9p` or `Hdcp=0,6.5`
+//! for the handicap and komi. The `[Data]` section holds one move per line, like `QD,B1,0` for
+//! the point, the color with the move number, and the time used. Points count columns from the
+//! left and rows from the bottom, starting at `A`, and points outside the board are passes. Boards
+//! go up to 25x25, so passes are written as `ZZ`.
+//! Stones with move number 0 are handicap stones.
+
+use crate::{GameTree, BadukError, BadukErrorKind, GameResult, Color, Position};
+use crate::record::{self, Record};
+
+/// Parses a UGF file into a `GameTree`
+pub fn parse(input: &str) -> Result<GameTree, BadukError> {
+    let mut header = vec![];
+    let mut data = vec![];
+    let mut section = None;
+    for line in input.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if line.starts_with('[') {
+            section = Some(line.to_ascii_lowercase());
+            continue;
+        }
+        match section.as_deref() {
+            Some("[header]") => header.extend(line.split_once('=')),
+            Some("[data]") => data.push(line),
+            _ => {}
+        }
+    }
+    if header.is_empty() && data.is_empty() {
+        return Err(BadukErrorKind::InvalidInput.into());
+    }
+
+    let value = |key: &str| {
+        header
+            .iter()
+            .find(|(k, _)| k.trim().eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.trim())
+            .filter(|value| !value.is_empty())
+    };
+    let size = value("Size").map_or(Ok(19), str::parse).map_err(BadukError::invalid_input)?;
+    if size == 0 || size > 25 {
+        return Err(BadukErrorKind::InvalidInput.into());
+    }
+    let mut record = Record::new(size, size);
+    record.name = value("Title").map(str::to_string);
+    record.place = value("Place").map(str::to_string);
+    record.date = value("Date").and_then(|date| date.split(',').next()).map(|date| date.replace('/', "-"));
+    for (key, color) in &[("PlayerB", Color::Black), ("PlayerW", Color::White)] {
+        if let Some(player) = value(key) {
+            let mut fields = player.split(',').map(str::trim);
+            let name = fields.next().map(str::to_string).filter(|name| !name.is_empty());
+            let rank = fields.next().map(str::to_string).filter(|rank| !rank.is_empty());
+            match color {
+                Color::Black => {
+                    record.black_player = name;
+                    record.black_rank = rank;
+                }
+                Color::White => {
+                    record.white_player = name;
+                    record.white_rank = rank;
+                }
+            }
+        }
+    }
+    if let Some(handicap) = value("Hdcp") {
+        let mut fields = handicap.split(',').map(str::trim);
+        record.handicap = fields.next().and_then(|stones| stones.parse().ok()).unwrap_or(0);
+        record.komi = fields.next().and_then(|komi| komi.parse().ok());
+    }
+    record.result = value("Winner").and_then(parse_result);
+
+    for line in data {
+        let fields = line.split(',').map(str::trim).collect::<Vec<_>>();
+        if fields.len() < 2 {
+            return Err(BadukErrorKind::InvalidInput.into());
+        }
+        let (color, number) = if let Some(number) = fields[1].strip_prefix('B') {
+            (Color::Black, number)
+        } else if let Some(number) = fields[1].strip_prefix('W') {
+            (Color::White, number)
+        } else {
+            return Err(BadukErrorKind::InvalidInput.into());
+        };
+        let number = number.parse::<u32>().map_err(BadukError::invalid_input)?;
+        let pos = parse_point(fields[0], size);
+        match (number, pos) {
+            (0, Some(pos)) => record.setup.push((color, pos)),
+            (0, None) => {}
+            _ => record.moves.push((color, pos)),
+        }
+    }
+    record.into_game_tree()
+}
+
+/// Parses a UGF file from raw bytes. Data that is not valid UTF-8 is read as Shift_JIS.
+pub fn parse_bytes(input: &[u8]) -> Result<GameTree, BadukError> {
+    parse(&record::decode(input, encoding_rs::SHIFT_JIS))
+}
+
+/// Writes the main line of a `GameTree` as a UGF file, failing for boards that are not square or
+/// larger than 25x25
+pub fn write(game: &GameTree) -> Result<String, BadukError> {
+    let record = Record::from_game_tree(game);
+    record.check_size(1..=25)?;
+    let mut lines = vec!["[Header]".to_string(), "Lang=JP".to_string()];
+    if let Some(ref name) = record.name {
+        lines.push(format!("Title={}", name));
+    }
+    if let Some(ref place) = record.place {
+        lines.push(format!("Place={}", place));
+    }
+    if let Some(ref date) = record.date {
+        lines.push(format!("Date={}", date.replace('-', "/")));
+    }
+    let players = [
+        ("PlayerB", &record.black_player, &record.black_rank),
+        ("PlayerW", &record.white_player, &record.white_rank),
+    ];
+    for (key, name, rank) in players.iter() {
+        if name.is_some() || rank.is_some() {
+            lines.push(format!(
+                "{}={},{}",
+                key,
+                name.as_deref().unwrap_or_default(),
+                rank.as_deref().unwrap_or_default()
+            ));
+        }
+    }
+    if let Some(result) = record.result {
+        let winner = |color: Color| match color {
+            Color::Black => "B",
+            Color::White => "W",
+        };
+        match result {
+            GameResult::WinByScore(color, score) => lines.push(format!("Winner={},{}", winner(color), score)),
+            GameResult::WinByResignation(color) => lines.push(format!("Winner={},C", winner(color))),
+            GameResult::WinByTime(color) => lines.push(format!("Winner={},T", winner(color))),
+            GameResult::WinByForfeit(color) | GameResult::Win(color) => {
+                lines.push(format!("Winner={}", winner(color)))
+            }
+            GameResult::Draw => lines.push("Winner=D".to_string()),
+            _ => {}
+        }
+    }
+    lines.push(format!("Hdcp={},{}", record.handicap, record.komi.unwrap_or(0.0)));
+    lines.push(format!("Size={}", record.width));
+    lines.push(format!("Moves={}", record.moves.len()));
+    lines.push("[Data]".to_string());
+    let color = |color: Color| match color {
+        Color::Black => 'B',
+        Color::White => 'W',
+    };
+    for (stone, pos) in &record.setup {
+        lines.push(format!("{},{}0,0", point(*pos, record.width), color(*stone)));
+    }
+    for (number, (stone, pos)) in (1..).zip(&record.moves) {
+        let pos = pos.map_or_else(|| PASS.to_string(), |pos| point(pos, record.width));
+        lines.push(format!("{},{}{},0", pos, color(*stone), number));
+    }
+    let mut out = lines.join("\n");
+    out.push('\n');
+    Ok(out)
+}
+
+/// Off the board for every size up to 25x25
+const PASS: &str = "ZZ";
+
+/// Reads points like `QD`, counting rows from the bottom
+fn parse_point(value: &str, size: u32) -> Option<Position> {
+    let bytes = value.as_bytes();
+    if bytes.len() != 2 {
+        return None;
+    }
+    let index = |c: u8| u32::from(c.to_ascii_uppercase()).checked_sub(u32::from(b'A')).filter(|c| *c < size);
+    let x = index(bytes[0])?;
+    let y = index(bytes[1])?;
+    Some(Position::from((x + 1, size - y)))
+}
+
+/// Writes a point on a board of at most 25x25, counting rows from the bottom
+fn point(pos: Position, size: u32) -> String {
+    let letter = |value: u32| std::char::from_u32('A' as u32 + value).unwrap_or('Z');
+    format!("{}{}", letter(pos.x() - 1), letter(size - pos.y()))
+}
+
+/// Reads winners like `B,C` for resignation, `W,2.5` or `D` for a draw
+fn parse_result(value: &str) -> Option<GameResult> {
+    let mut fields = value.split(',').map(str::trim);
+    let color = match fields.next()? {
+        "B" => Color::Black,
+        "W" => Color::White,
+        "D" | "0" => return Some(GameResult::Draw),
+        _ => return None,
+    };
+    Some(match fields.next().unwrap_or_default() {
+        "C" | "R" => GameResult::WinByResignation(color),
+        "T" => GameResult::WinByTime(color),
+        margin => match margin.parse() {
+            Ok(score) => GameResult::WinByScore(color, score),
+            Err(_) => GameResult::Win(color),
+        },
+    })
+}
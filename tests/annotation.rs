@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod annotation_tests {
+    use baduk_rs::{GameTree, Color, Emphasis, MoveAnnotation, PositionAnnotation, BadukErrorKind};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn it_reads_annotations_from_sgf() {
+        let game = GameTree::try_from("(;SZ[19]GB[2];B[pd]BM[1]HO[2]V[-3.5];W[dd]IT[]DM[1];B[dp]TE[2]UC[1])").unwrap();
+        assert_eq!(game.position_annotation(0), Some(PositionAnnotation::GoodForBlack(Emphasis::Emphasized)));
+        assert_eq!(game.move_annotation(1), Some(MoveAnnotation::BadMove(Emphasis::Normal)));
+        assert_eq!(game.hotspot(1), Some(Emphasis::Emphasized));
+        assert_eq!(game.position_value(1), Some(-3.5));
+        assert_eq!(game.move_annotation(2), Some(MoveAnnotation::Interesting));
+        assert_eq!(game.position_annotation(2), Some(PositionAnnotation::Even(Emphasis::Normal)));
+        assert_eq!(game.move_annotation(3), Some(MoveAnnotation::Tesuji(Emphasis::Emphasized)));
+        assert_eq!(game.position_annotation(3), Some(PositionAnnotation::Unclear(Emphasis::Normal)));
+        assert_eq!(game.move_annotation(0), None);
+        assert_eq!(game.hotspot(2), None);
+    }
+
+    #[test]
+    fn it_writes_annotations() {
+        let mut game = GameTree::new(19, 19);
+        let node = game.play_move((4, 4), Color::Black).unwrap();
+        game.set_move_annotation(node, Some(MoveAnnotation::Doubtful)).unwrap();
+        game.set_move_annotation(node, Some(MoveAnnotation::BadMove(Emphasis::Emphasized))).unwrap();
+        game.set_position_annotation(node, Some(PositionAnnotation::GoodForWhite(Emphasis::Normal)));
+        game.set_hotspot(node, Some(Emphasis::Normal));
+        game.set_position_value(node, Some(12.5));
+        assert_eq!(game.to_sgf(), "(;CA[UTF-8]SZ[19];B[dd]BM[2]GW[1]HO[1]V[12.5])");
+
+        game.set_move_annotation(node, None).unwrap();
+        game.set_hotspot(node, None);
+        assert_eq!(game.to_sgf(), "(;CA[UTF-8]SZ[19];B[dd]GW[1]V[12.5])");
+    }
+
+    #[test]
+    fn it_requires_a_move_for_move_annotations() {
+        let mut game = GameTree::new(19, 19);
+        match game.set_move_annotation(0, Some(MoveAnnotation::Tesuji(Emphasis::Normal))) {
+            Err(e) => assert_eq!(e.kind, BadukErrorKind::MissingMove),
+            Ok(_) => unreachable!(),
+        }
+        assert!(game.set_move_annotation(0, None).is_ok());
+    }
+
+    #[test]
+    fn it_finds_flagged_nodes() {
+        let game = GameTree::try_from("(;SZ[19];B[pd]BM[1];W[dd](;B[dp]HO[1];W[pp]BM[2])(;B[pp]TE[1]))").unwrap();
+        assert_eq!(game.bad_moves(), vec![1, 4]);
+        assert_eq!(game.hotspots(), vec![3]);
+        assert_eq!(game.flagged_nodes(), vec![1, 3, 4]);
+    }
+}
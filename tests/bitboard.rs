@@ -0,0 +1,127 @@
+#[cfg(test)]
+mod bitboard_tests {
+    use baduk_rs::{BitBoard, GameState, Color};
+
+    fn board(width: u32, height: u32, indices: &[usize]) -> BitBoard {
+        let mut board = BitBoard::new(width, height);
+        indices.iter().for_each(|index| board.set(*index));
+        board
+    }
+
+    #[test]
+    fn it_sets_and_clears_points() {
+        let mut board = BitBoard::new(19, 19);
+        assert!(board.is_empty());
+        board.set(0);
+        board.set(64);
+        board.set(360);
+        assert!(board.get(64));
+        assert!(!board.get(63));
+        assert_eq!(board.count(), 3);
+        assert_eq!(board.indices().collect::<Vec<_>>(), vec![0, 64, 360]);
+        board.clear(64);
+        assert_eq!(board.count(), 2);
+        assert_eq!(board.not().count(), 359);
+    }
+
+    #[test]
+    fn it_finds_neighbours_without_wrapping() {
+        // The corners of a 5x3 board
+        let corners = board(5, 3, &[0, 4, 10, 14]);
+        let expected = board(5, 3, &[1, 3, 5, 9, 11, 13]);
+        assert_eq!(corners.neighbours(), expected);
+
+        let center = board(5, 3, &[7]);
+        assert_eq!(center.neighbours(), board(5, 3, &[2, 6, 8, 12]));
+    }
+
+    #[test]
+    fn it_finds_neighbours_across_words() {
+        let width = 19;
+        let point = 2 * width + 7;
+        assert!(point < 64 && point + width >= 64);
+        let neighbours = board(19, 19, &[point]).neighbours();
+        assert_eq!(neighbours, board(19, 19, &[point - width, point - 1, point + 1, point + width]));
+
+        // Boards past 25x25 are stored on the heap
+        let large = board(52, 52, &[52 * 52 - 1]).neighbours();
+        assert_eq!(large, board(52, 52, &[52 * 51 - 1, 52 * 52 - 2]));
+    }
+
+    #[test]
+    fn it_floods_connected_points() {
+        let mask = board(4, 4, &[0, 1, 5, 9, 3, 7, 15]);
+        let chain = board(4, 4, &[0]).flood(&mask);
+        assert_eq!(chain, board(4, 4, &[0, 1, 5, 9]));
+        // Points 3 and 4 are at the ends of neighbouring rows, but not connected
+        let mask = board(4, 4, &[3, 4]);
+        assert_eq!(board(4, 4, &[3]).flood(&mask), board(4, 4, &[3]));
+    }
+
+    #[test]
+    fn it_exposes_stones_of_game_state() {
+        let state: GameState = "
+        .x.
+        o.x
+        ..."
+            .parse()
+            .unwrap();
+        assert_eq!(state.stones(Color::Black), &board(3, 3, &[1, 5]));
+        assert_eq!(state.stones(Color::White), &board(3, 3, &[3]));
+        assert_eq!(state.empty_points(), board(3, 3, &[0, 2, 4, 6, 7, 8]));
+    }
+
+    #[test]
+    fn it_captures_on_large_boards() {
+        let mut state = GameState::new(52, 52);
+        state = state.place_stone((52, 52), Color::White).unwrap();
+        state = state.place_stone((51, 52), Color::Black).unwrap();
+        state = state.place_stone((52, 51), Color::Black).unwrap();
+        assert_eq!(state.count_liberties((52, 52)), Some(0));
+        assert_eq!(state.remove_dead_stones(Color::White), vec![(52, 52).into()]);
+        assert_eq!(state.get_stone((52, 52)), None);
+        assert!(state.is_valid());
+    }
+
+    #[test]
+    fn it_matches_counting_liberties_point_by_point() {
+        // Fill a board with a fixed pseudo random pattern and compare with a plain count
+        let mut seed: u64 = 7;
+        let mut state = GameState::new(19, 19);
+        for y in 1..=19 {
+            for x in 1..=19 {
+                seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                match seed >> 62 {
+                    0 => state = state.add_stone((x, y), Color::Black).unwrap(),
+                    1 => state = state.add_stone((x, y), Color::White).unwrap(),
+                    _ => {}
+                }
+            }
+        }
+        for y in 1..=19u32 {
+            for x in 1..=19u32 {
+                let color = match state.get_stone((x, y)) {
+                    Some(color) => *color,
+                    None => continue,
+                };
+                // Walk the chain by hand
+                let mut chain = vec![(x, y)];
+                let mut liberties = vec![];
+                let mut i = 0;
+                while i < chain.len() {
+                    let (cx, cy) = chain[i];
+                    let neighbours = [(cx - 1, cy), (cx + 1, cy), (cx, cy - 1), (cx, cy + 1)];
+                    for n in neighbours.iter().filter(|(nx, ny)| *nx >= 1 && *nx <= 19 && *ny >= 1 && *ny <= 19) {
+                        match state.get_stone(*n) {
+                            Some(c) if *c == color && !chain.contains(n) => chain.push(*n),
+                            None if !liberties.contains(n) => liberties.push(*n),
+                            _ => {}
+                        }
+                    }
+                    i += 1;
+                }
+                assert_eq!(state.count_liberties((x, y)), Some(liberties.len() as u32), "at {}-{}", x, y);
+            }
+        }
+    }
+}
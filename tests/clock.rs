@@ -0,0 +1,135 @@
+#[cfg(test)]
+mod clock_tests {
+    use baduk_rs::{Clock, GameTree, GameResult, BadukErrorKind, Color, SgfToken, TimeControl};
+    use std::cell::Cell;
+    use std::convert::TryFrom;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    fn secs(seconds: u64) -> Duration {
+        Duration::from_secs(seconds)
+    }
+
+    fn fake_clock(control: TimeControl) -> (Clock<impl Fn() -> Duration>, Rc<Cell<Duration>>) {
+        let now = Rc::new(Cell::new(Duration::from_secs(0)));
+        let source = now.clone();
+        (Clock::with_time_source(control, move || source.get()), now)
+    }
+
+    fn advance(now: &Rc<Cell<Duration>>, seconds: u64) {
+        now.set(now.get() + secs(seconds));
+    }
+
+    #[test]
+    fn it_counts_down_absolute_time() {
+        let (mut clock, now) = fake_clock(TimeControl::Absolute { main: secs(60) });
+        let mut game = GameTree::new(9, 9);
+        clock.start(Color::Black);
+        advance(&now, 10);
+        assert_eq!(clock.time_left(Color::Black).main, secs(50));
+        let node = clock.play_move(&mut game, (3, 3)).unwrap();
+        assert_eq!(clock.running(), Some(Color::White));
+        assert_eq!(game.nodes[node].tokens[1], SgfToken::Time { color: Color::Black, time: 50 });
+        assert_eq!(game.recorded_time(node, Color::Black), Some((secs(50), None)));
+
+        advance(&now, 59);
+        assert_eq!(clock.flagged(), None);
+        advance(&now, 2);
+        assert_eq!(clock.flagged(), Some(Color::White));
+        let err = clock.play_move(&mut game, (4, 4)).unwrap_err();
+        assert_eq!(err.kind, BadukErrorKind::TimedOut);
+        assert_eq!(game.result(), Some(GameResult::WinByTime(Color::Black)));
+        assert_eq!(clock.running(), None);
+        assert_eq!(game.count_nodes(), 2);
+    }
+
+    #[test]
+    fn it_keeps_the_clock_running_on_illegal_moves() {
+        let (mut clock, now) = fake_clock(TimeControl::Absolute { main: secs(60) });
+        let mut game = GameTree::new(9, 9);
+        clock.start(Color::Black);
+        clock.play_move(&mut game, (3, 3)).unwrap();
+        advance(&now, 5);
+        assert!(clock.play_move(&mut game, (3, 3)).is_err());
+        advance(&now, 5);
+        let node = clock.pass(&mut game).unwrap();
+        assert_eq!(game.recorded_time(node, Color::White), Some((secs(50), None)));
+
+        clock.stop();
+        assert_eq!(clock.play_move(&mut game, (4, 4)).unwrap_err().kind, BadukErrorKind::ClockStopped);
+    }
+
+    #[test]
+    fn it_uses_byo_yomi_periods() {
+        let control = TimeControl::ByoYomi { main: secs(10), period: secs(30), periods: 3 };
+        let (mut clock, now) = fake_clock(control);
+        let mut game = GameTree::new(9, 9);
+        clock.start(Color::Black);
+        advance(&now, 35);
+        let node = clock.play_move(&mut game, (3, 3)).unwrap();
+        // 10 seconds of main time and 25 seconds of the first period, which is reset for the next move
+        assert_eq!(game.recorded_time(node, Color::Black), Some((secs(30), Some(3))));
+
+        clock.play_move(&mut game, (7, 7)).unwrap();
+        advance(&now, 65);
+        let node = clock.play_move(&mut game, (3, 7)).unwrap();
+        assert_eq!(game.recorded_time(node, Color::Black), Some((secs(30), Some(1))));
+
+        clock.play_move(&mut game, (7, 3)).unwrap();
+        advance(&now, 30);
+        assert_eq!(clock.flagged(), None);
+        advance(&now, 1);
+        assert_eq!(clock.flagged(), Some(Color::Black));
+        assert_eq!(clock.check_time(&mut game).unwrap(), Some(Color::Black));
+        assert_eq!(game.result(), Some(GameResult::WinByTime(Color::White)));
+    }
+
+    #[test]
+    fn it_uses_canadian_periods() {
+        let control = TimeControl::Canadian { main: secs(0), period: secs(60), stones: 2 };
+        let (mut clock, now) = fake_clock(control);
+        let mut game = GameTree::new(9, 9);
+        clock.start(Color::Black);
+        advance(&now, 20);
+        let node = clock.play_move(&mut game, (3, 3)).unwrap();
+        assert_eq!(game.recorded_time(node, Color::Black), Some((secs(40), Some(1))));
+        clock.play_move(&mut game, (7, 7)).unwrap();
+        advance(&now, 30);
+        let node = clock.play_move(&mut game, (3, 7)).unwrap();
+        assert_eq!(game.recorded_time(node, Color::Black), Some((secs(60), Some(2))));
+        assert_eq!(game.to_sgf(), "(;CA[UTF-8]SZ[9];B[cc]BL[40]OB[1];W[gg]WL[60]OW[1];B[cg]BL[60]OB[2])");
+    }
+
+    #[test]
+    fn it_adds_fischer_increments() {
+        let control = TimeControl::Fischer { main: secs(60), increment: Duration::from_millis(2500) };
+        let (mut clock, now) = fake_clock(control);
+        let mut game = GameTree::new(9, 9);
+        clock.start(Color::Black);
+        advance(&now, 10);
+        let node = clock.play_move(&mut game, (3, 3)).unwrap();
+        assert_eq!(game.nodes[node].tokens[1], SgfToken::Unknown(("BL".to_string(), "52.5".to_string())));
+        assert_eq!(game.recorded_time(node, Color::Black), Some((Duration::from_millis(52500), None)));
+    }
+
+    #[test]
+    fn it_stores_the_time_control() {
+        let mut game = GameTree::new(19, 19);
+        let control = TimeControl::ByoYomi { main: secs(3600), period: secs(60), periods: 5 };
+        game.set_time_control(Some(control));
+        assert_eq!(game.to_sgf(), "(;CA[UTF-8]SZ[19]TM[3600]OT[5x60 byo-yomi])");
+        assert_eq!(game.time_control(), Some(control));
+        assert_eq!(control.to_string(), "3600 + 5x60 byo-yomi");
+
+        let game = GameTree::try_from("(;TM[600]OT[25/300 Canadian])").unwrap();
+        assert_eq!(game.time_control(), Some(TimeControl::Canadian { main: secs(600), period: secs(300), stones: 25 }));
+        let game = GameTree::try_from("(;TM[90.5]OT[10 Fischer])").unwrap();
+        assert_eq!(
+            game.time_control(),
+            Some(TimeControl::Fischer { main: Duration::from_millis(90500), increment: secs(10) })
+        );
+        let game = GameTree::try_from("(;TM[300])").unwrap();
+        assert_eq!(game.time_control(), Some(TimeControl::Absolute { main: secs(300) }));
+        assert!(TimeControl::from_overtime(secs(0), Some("sudden death")).is_err());
+    }
+}
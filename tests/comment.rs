@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod comment_tests {
+    use baduk_rs::{GameTree, SgfToken, search_comments};
+    use regex::Regex;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn it_reads_comments_and_names() {
+        let game = GameTree::try_from("(;SZ[19]C[Game start];B[pd]N[Opening \\] move]C[A common Joseki])").unwrap();
+        assert_eq!(game.nodes[0].comment(), Some("Game start"));
+        assert_eq!(game.nodes[0].name(), None);
+        assert_eq!(game.nodes[1].comment(), Some("A common Joseki"));
+        assert_eq!(game.nodes[1].name().as_deref(), Some("Opening ] move"));
+    }
+
+    #[test]
+    fn it_writes_comments_and_names() {
+        let mut game = GameTree::new(19, 19);
+        game.nodes[0].set_comment(Some("first"));
+        game.nodes[0].set_name(Some("Root [1]"));
+        game.nodes[0].set_comment(Some("second"));
+        assert_eq!(game.nodes[0].tokens[1], SgfToken::Comment("second".to_string()));
+        assert_eq!(game.to_sgf(), "(;CA[UTF-8]SZ[19]C[second]N[Root [1\\]])");
+
+        game.nodes[0].set_comment(None);
+        assert_eq!(game.nodes[0].comment(), None);
+        assert_eq!(game.nodes[0].name().as_deref(), Some("Root [1]"));
+    }
+
+    #[test]
+    fn it_searches_a_game() {
+        let game = GameTree::try_from("(;SZ[19]C[Teaching game];B[pd]C[Joseki starts];W[dd](;B[qf]N[joseki variation])(;B[dp]C[Not a JOSEKI]))").unwrap();
+        assert_eq!(game.search_comments("joseki"), vec![1, 3, 4]);
+        assert_eq!(game.search_comments("teaching"), vec![0]);
+        assert!(game.search_comments("fuseki").is_empty());
+
+        let regex = Regex::new(r"^[Jj]oseki").unwrap();
+        assert_eq!(game.search_comments(&regex), vec![1, 3]);
+    }
+
+    #[test]
+    fn it_searches_a_collection() {
+        let games = vec![
+            GameTree::try_from("(;SZ[19];B[pd]C[joseki])").unwrap(),
+            GameTree::try_from("(;SZ[19]C[no match])").unwrap(),
+            GameTree::try_from("(;SZ[19];B[pd];W[dd]N[Joseki])").unwrap(),
+        ];
+        assert_eq!(search_comments(&games, "joseki"), vec![(0, 1), (2, 2)]);
+        assert_eq!(search_comments(&games, Regex::new("match$").unwrap()), vec![(1, 0)]);
+    }
+}
@@ -0,0 +1,90 @@
+#[cfg(test)]
+mod diagram_tests {
+    use baduk_rs::{GameTree, Color, DiagramNote};
+    use std::convert::TryFrom;
+
+    // Black starts a ko at E5 on move 4, and White takes it back with move 7
+    const KO: &str = "(;SZ[9]AB[dd][ce][ee][df]AW[ed][fe][ef];W[de];B[hb];W[bh];B[ee];W[aa];B[ii];W[de];B[])";
+
+    fn last_node(game: &GameTree) -> usize {
+        game.count_nodes() - 1
+    }
+
+    #[test]
+    fn it_numbers_moves() {
+        let game = GameTree::try_from(KO).unwrap();
+        let diagram = game.diagram(0, last_node(&game), 1).unwrap();
+        assert_eq!(diagram.moves.len(), 8);
+        assert_eq!(diagram.moves[0], (1, Color::White, Some((4, 5).into())));
+        assert_eq!(diagram.numbers[0], ((4, 5).into(), 1));
+        assert_eq!(diagram.numbers.len(), 5);
+        assert_eq!(
+            diagram.notes,
+            vec![DiagramNote::AtPoint(4, (5, 5).into()), DiagramNote::AtMove(7, 1), DiagramNote::Pass(8)]
+        );
+        // Stones captured during the sequence stay on the diagram
+        assert_eq!(diagram.board.get_stone((5, 5)), Some(&Color::Black));
+        assert_eq!(diagram.board.get_stone((4, 5)), Some(&Color::White));
+    }
+
+    #[test]
+    fn it_writes_a_text_diagram() {
+        let game = GameTree::try_from(KO).unwrap();
+        let expected = "  A B C D E F G H J
+9 5 . . . . . . . . 9
+8 . . . . . . . 2 . 8
+7 . . + . . . + . . 7
+6 . . . X O . . . . 6
+5 . . X 1 X O . . . 5
+4 . . . X O . . . . 4
+3 . . + . . . + . . 3
+2 . 3 . . . . . . . 2
+1 . . . . . . . . 6 1
+  A B C D E F G H J
+Black: 2, 4, 6, 8
+White: 1, 3, 5, 7
+4 at E5
+7 at 1
+8 pass
+";
+        assert_eq!(game.diagram(0, last_node(&game), 1).unwrap().to_string(), expected);
+    }
+
+    #[test]
+    fn it_starts_at_any_move() {
+        let game = GameTree::try_from(KO).unwrap();
+        let diagram = game.diagram(3, 7, 53).unwrap();
+        assert_eq!(diagram.moves.len(), 4);
+        assert_eq!(diagram.numbers[0], ((5, 5).into(), 53));
+        // The position after move 3 is shown without numbers
+        assert_eq!(diagram.board.get_stone((4, 5)), Some(&Color::White));
+        assert!(diagram.numbers.iter().all(|(pos, _)| *pos != (4, 5).into()));
+        assert_eq!(diagram.notes, vec![DiagramNote::AtPoint(56, (4, 5).into())]);
+
+        let text = diagram.to_string();
+        assert!(text.starts_with("   A  B  C  D  E  F  G  H  J\n9 54  .  .  .  .  .  .  .  . 9\n"));
+        assert!(text.ends_with("Black: 53, 55\nWhite: 54, 56\n56 at D5\n"));
+    }
+
+    #[test]
+    fn it_renders_an_svg_diagram() {
+        let game = GameTree::try_from(KO).unwrap();
+        let diagram = game.diagram(0, last_node(&game), 1).unwrap();
+        let svg = diagram.svg().to_string();
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="264" height="384" viewBox="0 0 264 384">"#));
+        assert!(svg.contains(">Black: 2, 4, 6, 8</text>"));
+        assert!(svg.contains(">4 at E5</text>"));
+
+        let svg = diagram.svg().coordinates(false).to_string();
+        assert!(svg.contains(">1</text>"));
+        assert!(svg.contains(">6</text>"));
+        assert!(!svg.contains(">7</text>"));
+    }
+
+    #[test]
+    fn it_requires_a_path() {
+        let game = GameTree::try_from("(;SZ[9](;B[aa])(;B[bb]))").unwrap();
+        assert!(game.diagram(1, 2, 1).is_err());
+        assert!(game.diagram(0, 2, 1).is_ok());
+    }
+}
@@ -0,0 +1,76 @@
+#[cfg(test)]
+mod encoding_tests {
+    use baduk_rs::{GameTree, Color};
+    use baduk_rs::sgf;
+    use std::convert::TryFrom;
+
+    fn encode(charset: &str, sgf: &str) -> Vec<u8> {
+        let encoding = encoding_rs::Encoding::for_label(charset.as_bytes()).unwrap();
+        let (bytes, _, had_errors) = encoding.encode(sgf);
+        assert!(!had_errors);
+        bytes.into_owned()
+    }
+
+    #[test]
+    fn it_decodes_shift_jis() {
+        // The second byte of 表 in Shift-JIS is a backslash
+        let bytes = encode("Shift_JIS", "(;CA[Shift_JIS]SZ[19]PB[本因坊秀策]C[表と裏];B[pd])");
+        let game = GameTree::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(game.player_name(Color::Black).as_deref(), Some("本因坊秀策"));
+        assert_eq!(game.count_nodes(), 2);
+        assert_eq!(game.to_sgf(), "(;CA[UTF-8]SZ[19]PB[本因坊秀策]C[表と裏];B[pd])");
+    }
+
+    #[test]
+    fn it_reads_shift_jis_backslashes_before_the_closing_bracket() {
+        let bytes = encode("Shift_JIS", "(;PB[表]CA[Shift_JIS]C[ソ];B[pd])");
+        let game = sgf::parse_bytes(&bytes).unwrap();
+        assert_eq!(game.player_name(Color::Black).as_deref(), Some("表"));
+        assert_eq!(game.count_nodes(), 2);
+
+        let mut bytes = encode("Shift_JIS", "(;CA[Shift_JIS]C[ソ];B[pd])");
+        bytes.extend(encode("Shift_JIS", "(;CA[Shift_JIS]PB[表])"));
+        let games = sgf::CollectionReader::new(bytes.as_slice()).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].count_nodes(), 2);
+        assert_eq!(games[1].player_name(Color::Black).as_deref(), Some("表"));
+    }
+
+    #[test]
+    fn it_decodes_gb2312() {
+        let bytes = encode("GB2312", "(;CA[gb2312]SZ[19]PW[柯洁]C[定式])");
+        let game = sgf::parse_bytes(&bytes).unwrap();
+        assert_eq!(game.player_name(Color::White).as_deref(), Some("柯洁"));
+        assert_eq!(game.to_sgf(), "(;CA[UTF-8]SZ[19]PW[柯洁]C[定式])");
+    }
+
+    #[test]
+    fn it_decodes_euc_kr() {
+        let bytes = encode("EUC-KR", "(;SZ[19]CA[EUC-KR]PB[이세돌])");
+        let game = sgf::parse_bytes(&bytes).unwrap();
+        assert_eq!(game.player_name(Color::Black).as_deref(), Some("이세돌"));
+    }
+
+    #[test]
+    fn it_falls_back_without_charset() {
+        let game = sgf::parse_bytes("(;SZ[19]PB[Jörg])".as_bytes()).unwrap();
+        assert_eq!(game.player_name(Color::Black).as_deref(), Some("Jörg"));
+
+        let game = sgf::parse_bytes(b"(;SZ[19]PB[J\xf6rg])").unwrap();
+        assert_eq!(game.player_name(Color::Black).as_deref(), Some("Jörg"));
+    }
+
+    #[test]
+    fn it_ignores_charset_text_in_values() {
+        assert_eq!(sgf::decode(b"(;C[CA[x]]PB[J\xf6rg])"), "(;C[CA[x]]PB[Jörg])");
+    }
+
+    #[test]
+    fn it_decodes_each_game_when_streaming() {
+        let mut bytes = encode("GB2312", "(;CA[GB2312]PB[柯洁])");
+        bytes.extend(encode("EUC-KR", "(;CA[EUC-KR]PB[이세돌])"));
+        let games = sgf::CollectionReader::new(bytes.as_slice()).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(games[0].player_name(Color::Black).as_deref(), Some("柯洁"));
+        assert_eq!(games[1].player_name(Color::Black).as_deref(), Some("이세돌"));
+    }
+}
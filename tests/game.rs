@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod game_tests {
-    use baduk_rs::{GameTree, GameState, Color, BadukErrorKind};
+    use baduk_rs::{GameTree, GameTreeNode, GameState, Color, BadukErrorKind};
     use sgf_parser::{parse};
 
     #[test]
@@ -240,4 +240,234 @@ mod game_tests {
         let game: GameTree = GameTree::try_from("(;SZ[19]W[ba]C[foobar];W[ab]AB[ca];CR[2019]B[ee])").unwrap();
         assert_eq!(game.count_nodes(), 3);
     }
+
+    #[test]
+    fn it_can_set_up_stones() {
+        use baduk_rs::SgfToken;
+        let mut game = GameTree::new(9, 9);
+        game.add_stone((3, 3), Color::Black).unwrap();
+        game.add_stone((3, 3), Color::White).unwrap();
+        game.add_stone((4, 4), Color::Black).unwrap();
+        assert_eq!(game.get_setup(0), vec![((3, 3).into(), Some(Color::White)), ((4, 4).into(), Some(Color::Black))]);
+        assert_eq!(game.to_sgf(), "(;CA[UTF-8]SZ[9]AW[cc]AB[dd])");
+
+        game.erase_stone((3, 3)).unwrap();
+        assert_eq!(game.current_state().unwrap().get_stone((3, 3)), None);
+        assert_eq!(game.nodes[0].tokens[2], SgfToken::Unknown(("AE".to_string(), "cc".to_string())));
+        assert_eq!(game.to_sgf(), "(;CA[UTF-8]SZ[9]AB[dd]AE[cc])");
+    }
+
+    #[test]
+    fn it_keeps_setup_of_one_color_together() {
+        let mut game = GameTree::new(9, 9);
+        game.add_stone((3, 3), Color::Black).unwrap();
+        game.add_stone((4, 4), Color::White).unwrap();
+        game.add_stone((5, 5), Color::Black).unwrap();
+        assert_eq!(game.to_sgf(), "(;CA[UTF-8]SZ[9]AB[cc][ee]AW[dd])");
+        assert_eq!(game.get_setup(0), vec![
+            ((3, 3).into(), Some(Color::Black)),
+            ((5, 5).into(), Some(Color::Black)),
+            ((4, 4).into(), Some(Color::White)),
+        ]);
+    }
+
+    #[test]
+    fn it_checks_setup_positions() {
+        let mut game = GameTree::new(9, 9);
+        let err = game.add_stone((10, 1), Color::Black).unwrap_err();
+        assert_eq!(err.kind, BadukErrorKind::InvalidPosition((10, 1).into()));
+        let err = game.erase_stone((1, 0)).unwrap_err();
+        assert_eq!(err.kind, BadukErrorKind::InvalidPosition((1, 0).into()));
+        assert_eq!(game.nodes[0].tokens.len(), 1);
+
+        let mut game = GameTree::default();
+        let err = game.add_stone((1, 1), Color::Black).unwrap_err();
+        assert_eq!(err.kind, BadukErrorKind::MissingGoBoard);
+    }
+
+    #[test]
+    fn it_can_set_up_a_position_mid_game() {
+        use std::convert::TryFrom;
+        let game = GameTree::try_from("(;SZ[9];B[cc];W[dd];AE[cc:dd]AB[ee]AW[ff];B[gg](;W[aa])(;AE[ee]))").unwrap();
+        let state = game.nodes[3].state.as_ref().unwrap();
+        assert_eq!(state.get_stone((3, 3)), None);
+        assert_eq!(state.get_stone((4, 4)), None);
+        assert_eq!(state.get_stone((5, 5)), Some(&Color::Black));
+        assert_eq!(state.get_stone((6, 6)), Some(&Color::White));
+        let state = game.nodes[5].state.as_ref().unwrap();
+        assert_eq!(state.get_stone((1, 1)), Some(&Color::White));
+        assert_eq!(state.get_stone((5, 5)), Some(&Color::Black));
+        let state = game.nodes[6].state.as_ref().unwrap();
+        assert_eq!(state.get_stone((5, 5)), None);
+        assert_eq!(state.get_stone((7, 7)), Some(&Color::Black));
+        assert_eq!(game.to_sgf(), "(;CA[UTF-8]SZ[9];B[cc];W[dd];AE[cc:dd]AB[ee]AW[ff];B[gg](;W[aa])(;AE[ee]))");
+    }
+
+    #[test]
+    fn it_updates_later_nodes_after_setup() {
+        let mut game = GameTree::new(9, 9);
+        let setup = game.create_new_node(0);
+        game.play_move((1, 2), Color::Black).unwrap();
+        game.play_move((2, 1), Color::Black).unwrap();
+        assert_eq!(game.current_state().unwrap().get_stone((1, 1)), None);
+
+        game.add_stone_on_node((1, 1), Color::White, setup).unwrap();
+        let state = game.current_state().unwrap();
+        assert_eq!(state.get_stone((1, 1)), None);
+        assert_eq!(state.captures().white, 1);
+        assert_eq!(game.get_setup(setup), vec![((1, 1).into(), Some(Color::White))]);
+    }
+
+    #[test]
+    fn it_tracks_side_to_move_and_ko() {
+        let state: GameState = "
+        .xo..
+        x.xo.
+        .xo..
+        .....
+        ....."
+            .parse()
+            .unwrap();
+        let mut game: GameTree = state.into();
+        game.play_move((4, 1), Color::White).unwrap();
+        let state = game.current_state().unwrap();
+        assert_eq!(state.to_move, Some(Color::Black));
+        assert_eq!(state.ko, None);
+
+        game.play_move((2, 2), Color::White).unwrap();
+        let state = game.current_state().unwrap();
+        assert_eq!(state.get_stone((3, 2)), None);
+        assert_eq!(state.ko, Some((3, 2).into()));
+        assert_eq!(state.to_move, Some(Color::Black));
+
+        game.play_move((5, 5), Color::Black).unwrap();
+        assert_eq!(game.current_state().unwrap().ko, None);
+    }
+
+    #[test]
+    fn it_keeps_the_full_state_when_created_from_game_state() {
+        let state: GameState = "size: 5x5\nto-move: black\nko: cb\ncaptures: black 1
+        .xo..
+        xo.o.
+        .xo..
+        .....
+        ....."
+            .parse()
+            .unwrap();
+        let mut game: GameTree = state.into();
+        assert_eq!(game.current_state().unwrap().captures().black, 1);
+        let err = game.play_move((3, 2), Color::Black).unwrap_err();
+        assert_eq!(err.kind, BadukErrorKind::RetakingKo);
+        assert!(game.play_move((3, 2), Color::White).is_ok());
+    }
+
+    #[test]
+    fn it_replays_states_between_checkpoints() {
+        use std::convert::TryFrom;
+        let sgf = "(;SZ[9];B[cb];W[db];B[bc];W[ec];B[cd];W[dd];B[ee](;W[dc];B[aa];W[bb])(;W[ab]AE[cb]))";
+        let full = GameTree::try_from(sgf).unwrap();
+        let mut game = GameTree::try_from(sgf).unwrap();
+        game.set_checkpoint_interval(Some(4));
+        assert_eq!(game.checkpoint_interval(), Some(4));
+        let stored: Vec<usize> = (0..game.count_nodes()).filter(|node| game.nodes[*node].state.is_some()).collect();
+        assert_eq!(stored, vec![0, 4, 8, game.current]);
+        for node in 0..game.count_nodes() {
+            assert_eq!(game.state(node).as_deref(), full.nodes[node].state.as_ref(), "at node {}", node);
+        }
+
+        assert!(game.nodes[9].state.is_none());
+        assert_eq!(game.cache_state(9), full.nodes[9].state.as_ref());
+        assert!(game.nodes[9].state.is_some());
+        game.release_states();
+        assert!(game.nodes[9].state.is_none());
+
+        game.set_checkpoint_interval(None);
+        assert!(game.nodes.iter().all(|node| node.state.is_some()));
+        assert_eq!(game.nodes[9].state, full.nodes[9].state);
+    }
+
+    #[test]
+    fn it_plays_and_edits_moves_with_checkpoints() {
+        let mut game = GameTree::new(9, 9);
+        game.set_checkpoint_interval(Some(2));
+        game.play_move((2, 1), Color::Black).unwrap();
+        game.play_move((3, 1), Color::White).unwrap();
+        game.play_move((1, 2), Color::Black).unwrap();
+        let node = game.play_move((4, 2), Color::White).unwrap();
+        game.play_move((2, 3), Color::Black).unwrap();
+        game.play_move((3, 3), Color::White).unwrap();
+        game.play_move((3, 2), Color::Black).unwrap();
+        // White captures 3-2, opening a ko
+        game.play_move((2, 2), Color::White).unwrap();
+        let stored: Vec<usize> = (0..game.count_nodes()).filter(|node| game.nodes[*node].state.is_some()).collect();
+        assert_eq!(stored, vec![0, 2, 4, 6, 8]);
+        assert_eq!(game.current_state().unwrap().get_stone((3, 2)), None);
+        assert_eq!(game.current_state().unwrap().captures().black, 1);
+
+        // Moves from a node without a stored board see the replayed board, including the ko
+        let err = game.play_move_as_variation((3, 2), Color::Black, 8).unwrap_err();
+        assert_eq!(err.kind, BadukErrorKind::RetakingKo);
+        let err = game.play_move_as_variation((3, 3), Color::Black, 7).unwrap_err();
+        assert_eq!(err.kind, BadukErrorKind::AlreadyOccupied((3, 3).into()));
+        let variation = game.play_move_as_variation((5, 5), Color::Black, 7).unwrap();
+        assert_eq!(game.state(variation).unwrap().get_stone((2, 2)), None);
+
+        // Stored boards below a changed setup are updated, others are replayed
+        game.erase_stone_on_node((4, 2), node).unwrap();
+        assert_eq!(game.state(5).unwrap().get_stone((4, 2)), None);
+        assert_eq!(game.state(6).unwrap().get_stone((4, 2)), None);
+        assert_eq!(game.current_state().unwrap().get_stone((4, 2)), None);
+    }
+
+    #[test]
+    fn it_finds_the_depth_of_nodes_added_by_hand() {
+        let mut game = GameTree::new(9, 9);
+        game.play_move((2, 1), Color::Black).unwrap();
+        let parent = game.play_move((3, 1), Color::White).unwrap();
+        game.nodes.push(GameTreeNode {
+            parent: Some(parent),
+            state: None,
+            tokens: vec![],
+            children: vec![],
+        });
+        let node = game.count_nodes() - 1;
+        game.nodes[parent].children.push(node);
+        assert_eq!(game.depth(node), 3);
+
+        game.set_checkpoint_interval(Some(2));
+        let stored: Vec<usize> = (0..game.count_nodes()).filter(|node| game.nodes[*node].state.is_some()).collect();
+        assert_eq!(stored, vec![0, 2]);
+        assert_eq!(game.state(node).unwrap().get_stone((3, 1)), Some(&Color::White));
+    }
+
+    #[test]
+    fn it_removes_nodes_and_their_children() {
+        use std::convert::TryFrom;
+        let mut game = GameTree::try_from("(;SZ[9];B[aa](;W[bb];B[cc])(;W[dd]))").unwrap();
+        assert_eq!(game.current, 4);
+        game.remove_node(2).unwrap();
+        assert_eq!(game.count_nodes(), 3);
+        assert_eq!(game.nodes[1].children, vec![2]);
+        assert_eq!(game.nodes[2].parent, Some(1));
+        assert_eq!(game.current, 2);
+        assert_eq!(game.to_sgf(), "(;CA[UTF-8]SZ[9];B[aa];W[dd])");
+
+        game.remove_node(2).unwrap();
+        assert_eq!(game.current, 1);
+        assert!(game.current_state().is_some());
+        assert_eq!(game.remove_node(0).unwrap_err().kind, BadukErrorKind::InvalidRootNode);
+    }
+
+    #[test]
+    fn it_ends_the_ko_on_a_pass() {
+        let mut game = GameTree::new(9, 9);
+        for (pos, color) in &[((2, 1), Color::Black), ((3, 1), Color::White), ((1, 2), Color::Black), ((4, 2), Color::White),
+            ((2, 3), Color::Black), ((3, 3), Color::White), ((3, 2), Color::Black), ((2, 2), Color::White)] {
+            game.play_move(*pos, *color).unwrap();
+        }
+        game.pass(Color::Black).unwrap();
+        let state = game.current_state().unwrap();
+        assert_eq!(state.ko, None);
+        assert_eq!(state.to_move, Some(Color::White));
+    }
 }
@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod gib_tests {
+    use baduk_rs::{gib, sgf, BadukErrorKind, GameResult, Color};
+    use std::fs;
+
+    #[test]
+    fn it_reads_gib_files() {
+        let input = fs::read_to_string("tests/fixtures/formats/sample.gib").unwrap();
+        let game = gib::parse(&input).unwrap();
+        assert_eq!(game.player_name(Color::Black).as_deref(), Some("Lee Sedol"));
+        assert_eq!(game.player_rank(Color::White).as_deref(), Some("9D"));
+        assert_eq!(game.komi(), Some(6.5));
+        assert_eq!(game.result(), Some(GameResult::WinByResignation(Color::White)));
+        assert_eq!(
+            game.to_sgf(),
+            "(;CA[UTF-8]SZ[19]DT[2014-01-26]PB[Lee Sedol]BR[9D]PW[Gu Li]WR[9D]KM[6.5]RE[W+R];B[pd];W[dp];B[pq];W[cd])"
+        );
+    }
+
+    #[test]
+    fn it_places_handicap_stones() {
+        let input = fs::read_to_string("tests/fixtures/formats/handicap.gib").unwrap();
+        let game = gib::parse(&input).unwrap();
+        assert_eq!(game.result(), Some(GameResult::WinByScore(Color::White, 3.5)));
+        assert_eq!(
+            game.to_sgf(),
+            "(;CA[UTF-8]SZ[19]PB[amateur]BR[3K]PW[teacher]WR[5D]KM[0]HA[2]RE[W+3.5]AB[dp][pd];W[cp];B[pp])"
+        );
+    }
+
+    #[test]
+    fn it_writes_what_it_reads() {
+        let input = "(;SZ[19]GN[Final]DT[2020-05-01]PB[Shin Jinseo]BR[9D]PW[Ke Jie]KM[6.5]RE[B+2.5];B[pd];W[];B[dd])";
+        let game = sgf::parse(input).unwrap();
+        let written = gib::write(&game).unwrap();
+        assert!(written.contains("\\[GAMEBLACKNAME=Shin Jinseo (9D)\\]"));
+        assert!(written.contains("GRLT:0,ZIPSU:25,GONGJE:65"));
+        assert!(written.contains("STO 0 3 1 3 3"));
+        assert_eq!(gib::parse(&written).unwrap().to_sgf(), game.to_sgf());
+    }
+
+    #[test]
+    fn it_rejects_points_off_the_board() {
+        for line in &["STO 0 1 1 60 3", "STO 0 1 1 200 3", "STO 0 1 1 3 19"] {
+            let input = format!("\\HS\n\\HE\n\\GS\n{}\n\\GE\n", line);
+            let err = gib::parse(&input).unwrap_err();
+            assert_eq!(err.kind, BadukErrorKind::InvalidInput);
+        }
+    }
+
+    #[test]
+    fn it_only_writes_19x19_boards() {
+        for input in &["(;SZ[9];B[cc])", "(;SZ[19:13];B[cc])"] {
+            let err = gib::write(&sgf::parse(input).unwrap()).unwrap_err();
+            assert_eq!(err.kind, BadukErrorKind::InvalidInputSize);
+        }
+    }
+
+    #[test]
+    fn it_decodes_euc_kr() {
+        let input = "\\HS\n\\[GAMEBLACKNAME=이세돌 (9D)\\]\n\\HE\n\\GS\nSTO 0 2 1 15 3\n\\GE\n";
+        let (bytes, _, _) = encoding_rs::EUC_KR.encode(input);
+        let game = gib::parse_bytes(&bytes).unwrap();
+        assert_eq!(game.player_name(Color::Black).as_deref(), Some("이세돌"));
+        assert!(gib::parse("(;SZ[19])").is_err());
+    }
+}
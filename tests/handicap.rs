@@ -0,0 +1,85 @@
+#[cfg(test)]
+mod handicap_tests {
+    use baduk_rs::{GameTree, Color, SgfToken, BadukErrorKind, Position, fixed_handicap_positions};
+
+    #[test]
+    fn it_places_fixed_handicap_on_19x19() {
+        let positions = fixed_handicap_positions(19, 19, 9).unwrap();
+        let expected: Vec<Position> = vec![
+            (4, 16), (16, 4), (4, 4), (16, 16), (4, 10), (16, 10), (10, 16), (10, 4), (10, 10)
+        ].into_iter().map(|p: (u32, u32)| p.into()).collect();
+        assert_eq!(positions, expected);
+
+        let positions = fixed_handicap_positions(19, 19, 2).unwrap();
+        assert_eq!(positions, vec![(4, 16).into(), (16, 4).into()]);
+    }
+
+    #[test]
+    fn it_places_fixed_handicap_on_small_boards() {
+        let positions = fixed_handicap_positions(13, 13, 5).unwrap();
+        assert_eq!(positions[0], (4, 10).into());
+        assert_eq!(positions[4], (7, 7).into());
+
+        let positions = fixed_handicap_positions(9, 9, 4).unwrap();
+        assert_eq!(positions, vec![(3, 7).into(), (7, 3).into(), (3, 3).into(), (7, 7).into()]);
+    }
+
+    #[test]
+    fn it_rejects_invalid_fixed_handicap() {
+        match fixed_handicap_positions(19, 19, 10) {
+            Err(e) => assert_eq!(e.kind, BadukErrorKind::InvalidHandicap(10)),
+            Ok(_) => unreachable!(),
+        }
+        assert!(fixed_handicap_positions(19, 19, 1).is_err());
+        assert!(fixed_handicap_positions(10, 10, 5).is_err());
+        assert!(fixed_handicap_positions(10, 10, 4).is_ok());
+    }
+
+    #[test]
+    fn it_writes_handicap_tokens_to_root() {
+        let mut game = GameTree::new(19, 19);
+        assert_eq!(game.next_to_play(), Color::Black);
+        game.place_fixed_handicap(3).unwrap();
+
+        let root = game.get_node(game.root).unwrap();
+        assert_eq!(root.tokens[1], SgfToken::Handicap(3));
+        let added = root.tokens.iter()
+            .filter(|t| matches!(t, SgfToken::Add { color: Color::Black, .. }))
+            .count();
+        assert_eq!(added, 3);
+        assert_eq!(game.current_state().unwrap().get_stone((4, 16)), Some(&Color::Black));
+        assert_eq!(game.next_to_play(), Color::White);
+
+        game.play_move((16, 16), Color::White).unwrap();
+        assert_eq!(game.next_to_play(), Color::Black);
+    }
+
+    #[test]
+    fn it_places_free_handicap() {
+        let mut game = GameTree::new(9, 9);
+        let positions = vec![(3, 3).into(), (5, 5).into(), (7, 7).into()];
+        game.place_free_handicap(&positions).unwrap();
+        let state = game.current_state().unwrap();
+        assert_eq!(state.get_stone((5, 5)), Some(&Color::Black));
+        assert_eq!(game.next_to_play(), Color::White);
+    }
+
+    #[test]
+    fn it_rejects_invalid_free_handicap() {
+        let mut game = GameTree::new(9, 9);
+        match game.place_free_handicap(&[(3, 3).into(), (3, 3).into()]) {
+            Err(e) => assert_eq!(e.kind, BadukErrorKind::AlreadyOccupied((3, 3).into())),
+            Ok(_) => unreachable!(),
+        }
+        match game.place_free_handicap(&[(3, 3).into(), (10, 3).into()]) {
+            Err(e) => assert_eq!(e.kind, BadukErrorKind::InvalidPosition((10, 3).into())),
+            Ok(_) => unreachable!(),
+        }
+
+        let _ = game.play_move((5, 5), Color::Black);
+        match game.place_fixed_handicap(2) {
+            Err(e) => assert_eq!(e.kind, BadukErrorKind::BoardNotEmpty),
+            Ok(_) => unreachable!(),
+        }
+    }
+}
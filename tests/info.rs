@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod info_tests {
+    use baduk_rs::{GameTree, GameInfo, GameResult, Color, RuleSet, SgfToken};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn it_reads_game_info_from_sgf() {
+        let game = GameTree::try_from("(;SZ[19]PB[Honinbo Shusaku]BR[7d]PW[Gennan Inseki]WR[8d]KM[6.5]RE[B+3.5]DT[1846-09-11]RU[Japanese]EV[Ear-reddening game];B[qd])").unwrap();
+        let info = game.game_info();
+        assert_eq!(info.black_player.as_deref(), Some("Honinbo Shusaku"));
+        assert_eq!(info.white_player.as_deref(), Some("Gennan Inseki"));
+        assert_eq!(info.black_rank.as_deref(), Some("7d"));
+        assert_eq!(info.white_rank.as_deref(), Some("8d"));
+        assert_eq!(info.komi, Some(6.5));
+        assert_eq!(info.result, Some(GameResult::WinByScore(Color::Black, 3.5)));
+        assert_eq!(info.date.as_deref(), Some("1846-09-11"));
+        assert_eq!(info.rules, Some(RuleSet::Japanese));
+        assert_eq!(info.event.as_deref(), Some("Ear-reddening game"));
+    }
+
+    #[test]
+    fn it_parses_results() {
+        assert_eq!("W+R".parse::<GameResult>().unwrap(), GameResult::WinByResignation(Color::White));
+        assert_eq!("B+Time".parse::<GameResult>().unwrap(), GameResult::WinByTime(Color::Black));
+        assert_eq!("W+F".parse::<GameResult>().unwrap(), GameResult::WinByForfeit(Color::White));
+        assert_eq!("B+".parse::<GameResult>().unwrap(), GameResult::Win(Color::Black));
+        assert_eq!("0".parse::<GameResult>().unwrap(), GameResult::Draw);
+        assert_eq!("Void".parse::<GameResult>().unwrap(), GameResult::Void);
+        assert_eq!("?".parse::<GameResult>().unwrap(), GameResult::Unknown);
+        assert!("X+R".parse::<GameResult>().is_err());
+        assert!("B+lots".parse::<GameResult>().is_err());
+        assert_eq!(GameResult::WinByScore(Color::White, 0.5).to_string(), "W+0.5");
+    }
+
+    #[test]
+    fn it_reads_void_result() {
+        let game = GameTree::try_from("(;SZ[19]RE[Void])").unwrap();
+        assert_eq!(game.result(), Some(GameResult::Void));
+    }
+
+    #[test]
+    fn it_writes_game_info_tokens() {
+        let mut game = GameTree::new(19, 19);
+        game.set_player_name(Color::Black, Some("Lee Sedol"));
+        game.set_komi(Some(7.5));
+        game.set_result(Some(GameResult::Void));
+        game.set_player_name(Color::Black, Some("Cho Chikun"));
+
+        let root = game.get_node(game.root).unwrap();
+        assert_eq!(root.tokens.len(), 4);
+        assert_eq!(root.tokens[1], SgfToken::PlayerName { color: Color::Black, name: "Cho Chikun".to_string() });
+        assert_eq!(root.tokens[2], SgfToken::Komi(7.5));
+        assert_eq!(root.tokens[3], SgfToken::Unknown(("RE".to_string(), "Void".to_string())));
+
+        game.set_komi(None);
+        assert_eq!(game.komi(), None);
+        assert_eq!(game.get_node(game.root).unwrap().tokens.len(), 3);
+    }
+
+    #[test]
+    fn it_round_trips_game_info() {
+        let info = GameInfo {
+            black_player: Some("Black".to_string()),
+            white_player: Some("White".to_string()),
+            komi: Some(0.5),
+            result: Some(GameResult::WinByResignation(Color::White)),
+            rules: Some(RuleSet::Chinese),
+            ..GameInfo::default()
+        };
+        let mut game = GameTree::new(9, 9);
+        game.set_game_info(&info);
+        assert_eq!(game.game_info(), info);
+    }
+}
@@ -0,0 +1,99 @@
+#[cfg(test)]
+mod markup_tests {
+    use baduk_rs::{GameTree, Markup, Color, SgfToken, BadukErrorKind};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn it_reads_markup_from_sgf() {
+        let game = GameTree::try_from("(;SZ[19];B[dd]LB[dd:A][ee:1]TR[aa]SQ[bb]CR[cc:cd]MA[ff]AR[aa:bb]LN[gg:hh]DD[ii])").unwrap();
+        let markup = game.get_markup(1);
+        assert_eq!(markup, vec![
+            Markup::Label((4, 4).into(), "A".to_string()),
+            Markup::Label((5, 5).into(), "1".to_string()),
+            Markup::Triangle((1, 1).into()),
+            Markup::Square((2, 2).into()),
+            Markup::Circle((3, 3).into()),
+            Markup::Circle((3, 4).into()),
+            Markup::Cross((6, 6).into()),
+            Markup::Arrow((1, 1).into(), (2, 2).into()),
+            Markup::Line((7, 7).into(), (8, 8).into()),
+            Markup::Dimmed((9, 9).into()),
+        ]);
+    }
+
+    #[test]
+    fn it_writes_markup_tokens() {
+        let mut game = GameTree::new(9, 9);
+        let node = game.play_move((5, 5), Color::Black).unwrap();
+        game.add_markup(node, Markup::Circle((5, 5).into())).unwrap();
+        game.add_markup(node, Markup::Circle((3, 3).into())).unwrap();
+        game.add_markup(node, Markup::Label((4, 4).into(), "a:b".to_string())).unwrap();
+        game.add_markup(node, Markup::Arrow((1, 1).into(), (5, 5).into())).unwrap();
+        game.add_markup(node, Markup::Dimmed((9, 9).into())).unwrap();
+        assert_eq!(game.to_sgf(), "(;CA[UTF-8]SZ[9];B[ee]CR[ee][cc]LB[dd:a\\:b]AR[aa:ee]DD[ii])");
+    }
+
+    #[test]
+    fn it_groups_markup_of_the_same_kind() {
+        let mut game = GameTree::new(9, 9);
+        game.add_markup(0, Markup::Circle((3, 3).into())).unwrap();
+        game.add_markup(0, Markup::Triangle((4, 4).into())).unwrap();
+        game.add_markup(0, Markup::Circle((5, 5).into())).unwrap();
+        let written = game.to_sgf();
+        assert_eq!(written, "(;CA[UTF-8]SZ[9]CR[cc][ee]TR[dd])");
+
+        let game = GameTree::try_from(written.as_str()).unwrap();
+        assert_eq!(game.get_markup(0), vec![
+            Markup::Circle((3, 3).into()),
+            Markup::Circle((5, 5).into()),
+            Markup::Triangle((4, 4).into()),
+        ]);
+    }
+
+    #[test]
+    fn it_validates_markup_against_board_size() {
+        let mut game = GameTree::new(9, 9);
+        match game.add_markup(0, Markup::Triangle((10, 1).into())) {
+            Err(e) => assert_eq!(e.kind, BadukErrorKind::InvalidPosition((10, 1).into())),
+            Ok(_) => unreachable!(),
+        }
+        match game.add_markup(0, Markup::Line((1, 1).into(), (1, 12).into())) {
+            Err(e) => assert_eq!(e.kind, BadukErrorKind::InvalidPosition((1, 12).into())),
+            Ok(_) => unreachable!(),
+        }
+        assert!(game.add_markup(0, Markup::Arrow((2, 2).into(), (2, 2).into())).is_err());
+        assert!(game.get_markup(0).is_empty());
+
+        let mut game = GameTree::default();
+        match game.add_markup(0, Markup::Square((1, 1).into())) {
+            Err(e) => assert_eq!(e.kind, BadukErrorKind::MissingGoBoard),
+            Ok(_) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn it_replaces_conflicting_markup() {
+        let mut game = GameTree::new(19, 19);
+        game.add_markup(0, Markup::Triangle((3, 3).into())).unwrap();
+        game.add_markup(0, Markup::Label((3, 3).into(), "A".to_string())).unwrap();
+        game.add_markup(0, Markup::Square((3, 3).into())).unwrap();
+        game.add_markup(0, Markup::Label((3, 3).into(), "B".to_string())).unwrap();
+        assert_eq!(game.get_markup(0), vec![
+            Markup::Square((3, 3).into()),
+            Markup::Label((3, 3).into(), "B".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn it_removes_markup() {
+        let mut game = GameTree::try_from("(;SZ[19]CR[aa:ab]XX[keep])").unwrap();
+        assert!(game.remove_markup(0, &Markup::Circle((1, 1).into())));
+        assert!(!game.remove_markup(0, &Markup::Circle((1, 1).into())));
+        assert_eq!(game.get_markup(0), vec![Markup::Circle((1, 2).into())]);
+
+        game.clear_markup(0);
+        assert!(game.get_markup(0).is_empty());
+        let tokens = &game.get_node(0).unwrap().tokens;
+        assert_eq!(tokens[tokens.len() - 1], SgfToken::Unknown(("XX".to_string(), "keep".to_string())));
+    }
+}
@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod ngf_tests {
+    use baduk_rs::{ngf, sgf, BadukErrorKind, GameResult, Color};
+    use std::fs;
+
+    #[test]
+    fn it_reads_ngf_files() {
+        let input = fs::read_to_string("tests/fixtures/formats/sample.ngf").unwrap();
+        let game = ngf::parse(&input).unwrap();
+        assert_eq!(game.player_name(Color::White).as_deref(), Some("Cho Chikun"));
+        assert_eq!(game.player_rank(Color::Black).as_deref(), Some("9p"));
+        assert_eq!(game.komi(), Some(6.5));
+        assert_eq!(game.result(), Some(GameResult::WinByResignation(Color::White)));
+        assert_eq!(
+            game.to_sgf(),
+            "(;CA[UTF-8]SZ[19]GN[Meijin League]DT[2003-05-05]PB[O Meien]BR[9p]PW[Cho Chikun]WR[9p]KM[6.5]RE[W+R];B[pd];W[dp];B[pq];W[cd])"
+        );
+    }
+
+    #[test]
+    fn it_writes_what_it_reads() {
+        let input = "(;SZ[13]GN[Club game]DT[2021-11-02]PB[Black]PW[White]WR[2k]KM[5.5]RE[B+12.5];B[jd];W[];B[dj])";
+        let game = sgf::parse(input).unwrap();
+        let written = ngf::write(&game).unwrap();
+        let lines = written.lines().collect::<Vec<_>>();
+        assert_eq!(lines[2], "White 2k");
+        assert_eq!(lines[7], "5");
+        assert_eq!(lines[8], "20211102");
+        assert_eq!(lines[10], "Black wins by 12.5 points!");
+        assert_eq!(lines[11], "3");
+        assert_eq!(lines[12], "PMABBKEEK");
+        assert_eq!(ngf::parse(&written).unwrap().to_sgf(), game.to_sgf());
+    }
+
+    #[test]
+    fn it_only_writes_square_boards_up_to_25() {
+        for input in &["(;SZ[9:13];B[cl])", "(;SZ[26];B[cc])"] {
+            let err = ngf::write(&sgf::parse(input).unwrap()).unwrap_err();
+            assert_eq!(err.kind, BadukErrorKind::InvalidInputSize);
+        }
+        let game = sgf::parse("(;SZ[25]KM[0];B[yy])").unwrap();
+        assert_eq!(ngf::parse(&ngf::write(&game).unwrap()).unwrap().to_sgf(), game.to_sgf());
+    }
+
+    #[test]
+    fn it_rejects_short_files() {
+        assert!(ngf::parse("Meijin League\n19\n").is_err());
+    }
+}
@@ -0,0 +1,76 @@
+#[cfg(all(test, feature = "json"))]
+mod ogs_tests {
+    use baduk_rs::{ogs, sgf, GameResult, Color};
+    use serde_json::{json, Value};
+    use std::fs;
+
+    fn fixture(name: &str) -> String {
+        fs::read_to_string(format!("tests/fixtures/ogs/{}.json", name)).unwrap()
+    }
+
+    #[test]
+    fn it_reads_even_games() {
+        let game = ogs::parse(&fixture("even")).unwrap();
+        assert_eq!(game.result(), Some(GameResult::WinByResignation(Color::White)));
+        assert_eq!(game.player_rank(Color::Black).as_deref(), Some("3d"));
+        assert_eq!(game.player_rank(Color::White).as_deref(), Some("3k"));
+        assert_eq!(
+            game.to_sgf(),
+            "(;CA[UTF-8]SZ[19]GN[Friendly Match]PB[kurosaki]BR[3d]PW[shiroyama]WR[3k]KM[6.5]RE[W+R]RU[Japanese]\
+             ;B[pd]MT[4210];W[dp]MT[5120.5];B[pq]MT[2830];W[cd]MT[7741];B[]MT[1200])"
+        );
+    }
+
+    #[test]
+    fn it_reads_free_handicap_stones() {
+        let game = ogs::parse(&fixture("handicap")).unwrap();
+        assert_eq!(game.result(), Some(GameResult::WinByScore(Color::White, 12.5)));
+        assert_eq!(
+            game.to_sgf(),
+            "(;CA[UTF-8]SZ[13]GN[Teaching game]PB[student]BR[12k]PW[teacher]WR[5d]KM[0.5]HA[3]RE[W+12.5]RU[Chinese]\
+             AB[dd][jj][jd];W[cj]MT[3400];B[gg]MT[2100])"
+        );
+    }
+
+    #[test]
+    fn it_writes_what_it_reads() {
+        for name in &["even", "handicap"] {
+            let input = fixture(name);
+            let game = ogs::parse(&input).unwrap();
+            let written = ogs::write(&game);
+            assert_eq!(ogs::parse(&written).unwrap().to_sgf(), game.to_sgf());
+
+            let original: Value = serde_json::from_str(&input).unwrap();
+            let written: Value = serde_json::from_str(&written).unwrap();
+            for key in &["width", "height", "komi", "handicap", "rules", "free_handicap_placement", "outcome"] {
+                assert_eq!(written[key], original[key], "{} differs for {}", key, name);
+            }
+            assert_eq!(written["players"]["black"]["username"], original["players"]["black"]["username"]);
+        }
+        let written: Value = serde_json::from_str(&ogs::write(&ogs::parse(&fixture("even")).unwrap())).unwrap();
+        assert_eq!(written["moves"], json!([[15, 3, 4210], [3, 15, 5120.5], [15, 16, 2830], [2, 3, 7741], [-1, -1, 1200]]));
+        assert_eq!(written["winner"], json!("white"));
+    }
+
+    #[test]
+    fn it_writes_setup_and_fixed_handicaps() {
+        let game = sgf::parse("(;SZ[9]HA[2]AB[gc][cg]AW[ee];W[cc];B[gg])").unwrap();
+        let written: Value = serde_json::from_str(&ogs::write(&game)).unwrap();
+        assert_eq!(written["free_handicap_placement"], json!(false));
+        assert_eq!(written["initial_state"], json!({"black": "", "white": "ee"}));
+        assert_eq!(written["initial_player"], json!("white"));
+        assert_eq!(written["moves"], json!([[2, 2, 0], [6, 6, 0]]));
+        // Fixed handicap stones are placed again at the standard points, and missing times are 0
+        assert_eq!(
+            ogs::parse(&written.to_string()).unwrap().to_sgf(),
+            "(;CA[UTF-8]SZ[9]HA[2]AB[cg][gc]AW[ee];W[cc]MT[0];B[gg]MT[0])"
+        );
+    }
+
+    #[test]
+    fn it_rejects_invalid_records() {
+        assert!(ogs::parse("{\"width\": 0, \"height\": 19}").is_err());
+        assert!(ogs::parse("{\"width\": 9, \"height\": 9, \"moves\": [[9, 0, 100]]}").is_err());
+        assert!(ogs::parse("not json").is_err());
+    }
+}
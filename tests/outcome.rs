@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod outcome_tests {
+    use baduk_rs::{GameTree, GameResult, BadukErrorKind, Color};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn it_records_results_on_the_root() {
+        let mut game = GameTree::new(9, 9);
+        game.play_move((3, 3), Color::Black).unwrap();
+        assert!(!game.is_over());
+        assert_eq!(game.resign(Color::Black).unwrap(), GameResult::WinByResignation(Color::White));
+        assert!(game.is_over());
+        assert_eq!(game.result(), Some(GameResult::WinByResignation(Color::White)));
+        assert_eq!(game.to_sgf(), "(;CA[UTF-8]SZ[9]RE[W+R];B[cc])");
+        assert_eq!(game.time_out(Color::White).unwrap_err().kind, BadukErrorKind::GameOver);
+    }
+
+    #[test]
+    fn it_ends_games_in_every_way() {
+        let cases = vec![
+            (GameTree::time_out as fn(&mut GameTree, Color) -> _, GameResult::WinByTime(Color::Black), "B+T"),
+            (GameTree::forfeit, GameResult::WinByForfeit(Color::Black), "B+F"),
+            (GameTree::resign, GameResult::WinByResignation(Color::Black), "B+R"),
+        ];
+        for (end, result, value) in cases {
+            let mut game = GameTree::new(9, 9);
+            assert_eq!(end(&mut game, Color::White).unwrap(), result);
+            assert_eq!(game.to_sgf(), format!("(;CA[UTF-8]SZ[9]RE[{}])", value));
+        }
+
+        let mut game = GameTree::new(9, 9);
+        assert_eq!(game.end_by_score(60.0, 66.5).unwrap(), GameResult::WinByScore(Color::White, 6.5));
+        assert_eq!(game.result().unwrap().score(), Some(6.5));
+
+        let mut game = GameTree::new(9, 9);
+        assert_eq!(game.end_by_score(70.0, 70.0).unwrap(), GameResult::Draw);
+        assert!(game.is_over());
+        assert_eq!(game.to_sgf(), "(;CA[UTF-8]SZ[9]RE[0])");
+
+        let mut game = GameTree::new(9, 9);
+        game.void().unwrap();
+        assert!(!game.is_over());
+        assert_eq!(game.to_sgf(), "(;CA[UTF-8]SZ[9]RE[Void])");
+    }
+
+    #[test]
+    fn it_tells_decisive_results() {
+        assert!(GameResult::WinByScore(Color::Black, 0.5).is_decisive());
+        assert!(GameResult::Win(Color::White).is_decisive());
+        assert!(GameResult::Draw.is_decisive());
+        assert!(!GameResult::Void.is_decisive());
+        assert!(!GameResult::Unknown.is_decisive());
+        assert_eq!("Jigo".parse::<GameResult>().unwrap(), GameResult::Draw);
+    }
+
+    #[test]
+    fn it_rejects_moves_after_the_end_in_strict_mode() {
+        let mut game = GameTree::try_from("(;SZ[9]RE[B+R];B[cc];W[dd])").unwrap();
+        assert_eq!(game.count_nodes(), 3);
+        assert!(game.is_over());
+        assert!(game.play_move((5, 5), Color::Black).is_ok());
+
+        game.set_strict(true);
+        assert!(game.is_strict());
+        assert_eq!(game.play_move((6, 6), Color::White).unwrap_err().kind, BadukErrorKind::GameOver);
+        assert_eq!(game.pass(Color::White).unwrap_err().kind, BadukErrorKind::GameOver);
+
+        game.set_result(Some(GameResult::Void));
+        assert!(game.play_move((6, 6), Color::White).is_ok());
+    }
+}
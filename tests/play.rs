@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod play_tests {
+    use baduk_rs::{Game, GameTree, GameResult, BadukErrorKind, Color, Position};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn it_alternates_players() {
+        let mut game = Game::new(9, 9);
+        assert_eq!(game.to_move(), Color::Black);
+        game.play((3, 3)).unwrap();
+        assert_eq!(game.to_move(), Color::White);
+        game.pass().unwrap();
+        assert_eq!(game.to_move(), Color::Black);
+        game.play((7, 7)).unwrap();
+        assert_eq!(game.state().get_stone((3, 3)), Some(&Color::Black));
+        assert_eq!(game.state().get_stone((7, 7)), Some(&Color::Black));
+        assert_eq!(
+            game.moves(),
+            vec![
+                (1, Color::Black, Some((3, 3).into())),
+                (2, Color::White, None),
+                (3, Color::Black, Some((7, 7).into())),
+            ]
+        );
+        assert_eq!(game.to_sgf(), "(;CA[UTF-8]SZ[9];B[cc];W[];B[gg])");
+    }
+
+    #[test]
+    fn it_rejects_illegal_moves_without_changing_the_game() {
+        let mut game = Game::new(9, 9);
+        game.play((3, 3)).unwrap();
+        let err = game.play((3, 3)).unwrap_err();
+        assert_eq!(err.kind, BadukErrorKind::AlreadyOccupied((3, 3).into()));
+        assert_eq!(game.to_move(), Color::White);
+        assert_eq!(game.tree().count_nodes(), 2);
+    }
+
+    #[test]
+    fn it_undoes_captures_and_ko() {
+        let mut game = Game::new(9, 9);
+        for pos in &[(2, 1), (3, 1), (1, 2), (4, 2), (2, 3), (3, 3), (3, 2)] {
+            game.play(*pos).unwrap();
+        }
+        let captured = game.play((2, 2)).unwrap();
+        assert_eq!(captured, vec![Position::from((3, 2))]);
+        assert_eq!(game.state().captures().black, 1);
+        let err = game.play((3, 2)).unwrap_err();
+        assert_eq!(err.kind, BadukErrorKind::RetakingKo);
+
+        assert!(game.undo());
+        assert_eq!(game.to_move(), Color::White);
+        assert_eq!(game.state().get_stone((3, 2)), Some(&Color::Black));
+        assert_eq!(game.state().captures().black, 0);
+        assert_eq!(game.state().ko, None);
+
+        assert!(game.redo());
+        assert_eq!(game.state().get_stone((3, 2)), None);
+        assert_eq!(game.state().ko, Some((3, 2).into()));
+        assert!(!game.redo());
+
+        // Passing ends the ko
+        game.pass().unwrap();
+        game.pass().unwrap();
+        assert!(game.play((3, 2)).is_ok());
+    }
+
+    #[test]
+    fn it_replaces_undone_moves_with_new_ones() {
+        let mut game = Game::new(9, 9);
+        game.play((1, 1)).unwrap();
+        game.play((2, 2)).unwrap();
+        game.play((3, 3)).unwrap();
+        assert!(game.undo());
+        assert!(game.undo());
+        game.play((5, 5)).unwrap();
+        assert!(!game.redo());
+        assert_eq!(game.tree().count_nodes(), 3);
+        assert_eq!(game.to_sgf(), "(;CA[UTF-8]SZ[9];B[aa];W[ee])");
+        assert!(game.undo());
+        assert!(game.undo());
+        assert!(!game.undo());
+        assert!(game.state().is_empty());
+    }
+
+    #[test]
+    fn it_ends_the_game_on_resignation() {
+        let mut game = Game::new(9, 9);
+        game.play((3, 3)).unwrap();
+        game.resign(Color::White).unwrap();
+        assert!(game.is_over());
+        assert_eq!(game.result(), Some(GameResult::WinByResignation(Color::Black)));
+        assert_eq!(game.play((4, 4)).unwrap_err().kind, BadukErrorKind::GameOver);
+        assert_eq!(game.pass().unwrap_err().kind, BadukErrorKind::GameOver);
+        assert_eq!(game.to_sgf(), "(;CA[UTF-8]SZ[9]RE[B+R];B[cc])");
+
+        assert!(game.undo());
+        assert!(!game.is_over());
+        assert!(game.redo());
+        assert!(game.is_over());
+        assert!(game.undo());
+        game.play((4, 4)).unwrap();
+        assert!(!game.redo());
+    }
+
+    #[test]
+    fn it_continues_games_from_a_tree() {
+        let tree = GameTree::try_from("(;SZ[9]AB[cc]PL[W];W[gg])").unwrap();
+        let mut game = Game::try_from(tree).unwrap();
+        assert_eq!(game.to_move(), Color::Black);
+        game.play((7, 3)).unwrap();
+        let tree = game.into_tree();
+        assert_eq!(tree.to_sgf(), "(;CA[UTF-8]SZ[9]AB[cc]PL[W];W[gg];B[gc])");
+
+        let err = Game::try_from(GameTree::default()).unwrap_err();
+        assert_eq!(err.kind, BadukErrorKind::MissingGoBoard);
+    }
+
+    #[test]
+    fn it_keeps_undone_moves_from_the_tree() {
+        let mut tree = GameTree::try_from("(;SZ[9];B[aa](;W[bb];B[cc])(;W[dd]))").unwrap();
+        tree.current = 2;
+        let mut game = Game::try_from(tree).unwrap();
+        game.play((7, 7)).unwrap();
+        assert!(game.undo());
+        assert!(game.undo());
+        game.play((5, 5)).unwrap();
+        assert!(!game.redo());
+        assert_eq!(game.to_sgf(), "(;CA[UTF-8]SZ[9];B[aa](;W[bb];B[cc])(;W[dd])(;W[ee]))");
+    }
+}
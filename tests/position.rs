@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod tests {
-    use baduk_rs::Position;
+    use baduk_rs::{BadukErrorKind, Coordinates, GameState, Notation, Position};
+    use std::convert::TryFrom;
 
     #[test]
     fn it_can_convert_from_usize_tuple() {
@@ -11,16 +12,151 @@ mod tests {
 
     #[test]
     fn it_can_convert_from_str() {
-        let pos: Position = "A1".into();
+        let pos: Position = "A1".parse().unwrap();
         assert_eq!(pos.x(), 1);
         assert_eq!(pos.y(), 1);
 
-        let pos: Position = "t19".into();
+        let pos: Position = "t19".parse().unwrap();
         assert_eq!(pos.x(), 19);
         assert_eq!(pos.y(), 19);
 
-        let pos: Position = "J12".into();
+        let pos = Position::try_from("J12").unwrap();
         assert_eq!(pos.x(), 9);
         assert_eq!(pos.y(), 12);
     }
+
+    #[test]
+    fn it_parses_all_notations() {
+        assert_eq!("D4".parse::<Position>().unwrap(), (4, 4).into());
+        assert_eq!("AB3".parse::<Position>().unwrap(), (27, 3).into());
+        assert_eq!("dc".parse::<Position>().unwrap(), (4, 3).into());
+        assert_eq!("aZ".parse::<Position>().unwrap(), (1, 52).into());
+        assert_eq!("4-3".parse::<Position>().unwrap(), (4, 3).into());
+        assert_eq!(" 16-17 ".parse::<Position>().unwrap(), (16, 17).into());
+    }
+
+    #[test]
+    fn it_parses_passes() {
+        assert_eq!(Position::parse_move("pass").unwrap(), None);
+        assert_eq!(Position::parse_move("PASS").unwrap(), None);
+        assert_eq!(Position::parse_move("Q16").unwrap(), Some((16, 16).into()));
+        let err = "pass".parse::<Position>().unwrap_err();
+        assert_eq!(err.kind, BadukErrorKind::InvalidCoordinate("pass".to_string()));
+    }
+
+    #[test]
+    fn it_rejects_invalid_coordinates() {
+        for value in &["", "A", "I5", "A0", "A+1", "Z99", "abc", "a1b", "4-", "-4", "0-1", "1-53", "x-y", "D4-", "ÅÅ"] {
+            match value.parse::<Position>() {
+                Err(e) => assert_eq!(e.kind, BadukErrorKind::InvalidCoordinate(value.to_string())),
+                Ok(pos) => panic!("{} parsed as {}", value, pos),
+            }
+        }
+    }
+
+    #[test]
+    fn it_parses_a_specific_notation() {
+        assert_eq!(Position::parse_notation("cd", Notation::Sgf).unwrap(), (3, 4).into());
+        assert!(Position::parse_notation("C4", Notation::Sgf).is_err());
+        assert!(Position::parse_notation("3-4", Notation::Gtp).is_err());
+        assert!(Position::parse_notation("cd", Notation::Numeric).is_err());
+    }
+
+    #[test]
+    fn it_formats_all_notations() {
+        let pos: Position = (9, 12).into();
+        assert_eq!(pos.format(Notation::Gtp).unwrap(), "J12");
+        assert_eq!(pos.format(Notation::Sgf).unwrap(), "il");
+        assert_eq!(pos.format(Notation::Numeric).unwrap(), "9-12");
+        assert_eq!(pos.to_string(), "9-12");
+
+        let pos: Position = (30, 27).into();
+        assert_eq!(pos.format(Notation::Gtp).unwrap(), "AE27");
+        assert_eq!(pos.format(Notation::Sgf).unwrap(), "DA");
+        assert_eq!(Position::format_move(None, Notation::Sgf).unwrap(), "pass");
+
+        for notation in &[Notation::Gtp, Notation::Sgf, Notation::Numeric] {
+            for x in 1..=52 {
+                let pos: Position = (x, 53 - x).into();
+                assert_eq!(Position::parse_notation(&pos.format(*notation).unwrap(), *notation).unwrap(), pos);
+            }
+        }
+    }
+
+    #[test]
+    fn it_does_not_format_positions_out_of_range() {
+        for notation in &[Notation::Gtp, Notation::Sgf, Notation::Numeric, Notation::Japanese] {
+            assert!(Position::from((0, 1)).format(*notation).is_err());
+            assert!(Position::from((53, 1)).format(*notation).is_err());
+            assert!(Position::from((1, 300)).format(*notation).is_err());
+        }
+        let coordinates = Coordinates::new(9, 9);
+        assert!(coordinates.format((10, 1).into(), Notation::Gtp).is_err());
+        assert!(coordinates.format((1, 0).into(), Notation::Japanese).is_err());
+    }
+
+    #[test]
+    fn it_converts_gtp_coordinates_for_a_board() {
+        let coordinates = Coordinates::new(19, 19);
+        assert_eq!(coordinates.parse("A1", Notation::Gtp).unwrap(), (1, 19).into());
+        assert_eq!(coordinates.parse("T19", Notation::Gtp).unwrap(), (19, 1).into());
+        assert_eq!(coordinates.parse("D16", Notation::Gtp).unwrap(), (4, 4).into());
+        assert_eq!(coordinates.format((4, 4).into(), Notation::Gtp).unwrap(), "D16");
+        assert!(coordinates.parse("A20", Notation::Gtp).is_err());
+        assert!(coordinates.parse("U1", Notation::Gtp).is_err());
+
+        let coordinates = Coordinates::new(9, 13);
+        assert_eq!(coordinates.parse("J1", Notation::Gtp).unwrap(), (9, 13).into());
+        assert_eq!(coordinates.format((1, 1).into(), Notation::Gtp).unwrap(), "A13");
+    }
+
+    #[test]
+    fn it_converts_sgf_coordinates_for_a_board() {
+        let coordinates = Coordinates::new(19, 19);
+        assert_eq!(coordinates.parse("pd", Notation::Sgf).unwrap(), (16, 4).into());
+        assert_eq!(coordinates.format((16, 4).into(), Notation::Sgf).unwrap(), "pd");
+        assert_eq!(coordinates.parse_move("tt", Notation::Sgf).unwrap(), None);
+        assert_eq!(coordinates.parse_move("", Notation::Sgf).unwrap(), None);
+        assert_eq!(coordinates.format_move(None, Notation::Sgf).unwrap(), "");
+        assert!(coordinates.parse("ta", Notation::Sgf).is_err());
+
+        let coordinates = Coordinates::new(21, 21);
+        assert_eq!(coordinates.parse_move("tt", Notation::Sgf).unwrap(), Some((20, 20).into()));
+    }
+
+    #[test]
+    fn it_converts_japanese_coordinates_for_a_board() {
+        let coordinates = Coordinates::new(19, 19);
+        assert_eq!(coordinates.parse("１七", Notation::Japanese).unwrap(), (19, 7).into());
+        assert_eq!(coordinates.parse("17四", Notation::Japanese).unwrap(), (3, 4).into());
+        assert_eq!(coordinates.parse("１９十九", Notation::Japanese).unwrap(), (1, 19).into());
+        assert_eq!(coordinates.format((16, 4).into(), Notation::Japanese).unwrap(), "４四");
+        assert_eq!(coordinates.format((10, 12).into(), Notation::Japanese).unwrap(), "１０十二");
+        assert!(coordinates.parse("２０一", Notation::Japanese).is_err());
+        assert!(coordinates.parse("１二十", Notation::Japanese).is_err());
+        assert!(coordinates.parse("一", Notation::Japanese).is_err());
+
+        assert_eq!(Position::parse_move("４四").unwrap(), Some((4, 4).into()));
+        assert_eq!(Position::from((33, 52)).format(Notation::Japanese).unwrap(), "３３五十二");
+    }
+
+    #[test]
+    fn it_agrees_on_orientation_across_notations() {
+        let state = GameState::new(13, 9);
+        let coordinates = state.coordinates();
+        for notation in &[Notation::Gtp, Notation::Sgf, Notation::Numeric, Notation::Japanese] {
+            for x in 1..=13 {
+                for y in 1..=9 {
+                    let pos: Position = (x, y).into();
+                    let text = coordinates.format(pos, *notation).unwrap();
+                    assert_eq!(coordinates.parse(&text, *notation).unwrap(), pos);
+                }
+            }
+        }
+        let corner: Position = (1, 9).into();
+        assert_eq!(coordinates.format(corner, Notation::Gtp).unwrap(), "A1");
+        assert_eq!(coordinates.format(corner, Notation::Sgf).unwrap(), "ai");
+        assert_eq!(coordinates.format(corner, Notation::Japanese).unwrap(), "１３九");
+        assert_eq!(coordinates.format_move(None, Notation::Gtp).unwrap(), "pass");
+    }
 }
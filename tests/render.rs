@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod render_tests {
+    use baduk_rs::{GameState, GameTree, Color, Orientation, star_points};
+
+    fn state() -> GameState {
+        "
+        .......
+        .......
+        .....x.
+        .......
+        ..o....
+        .......
+        ......."
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn it_renders_with_coordinates() {
+        let expected = "  A B C D E F G
+7 . . . . . . . 7
+6 . . . . . . . 6
+5 . . + . + X . 5
+4 . . . + . . . 4
+3 . . O . + . . 3
+2 . . . . . . . 2
+1 . . . . . . . 1
+  A B C D E F G
+";
+        assert_eq!(state().render().to_string(), expected);
+    }
+
+    #[test]
+    fn it_renders_without_coordinates() {
+        let out = state().render().coordinates(false).unicode(true).to_string();
+        assert_eq!(out.lines().nth(2), Some(" · · + · + ● ·"));
+        assert_eq!(out.lines().nth(4), Some(" · · ○ · + · ·"));
+        assert_eq!(out.lines().count(), 7);
+    }
+
+    #[test]
+    fn it_marks_the_last_move() {
+        let out = state().render().last_move(Some((6, 3).into())).to_string();
+        assert_eq!(out.lines().nth(3), Some("5 . . + . +(X). 5"));
+
+        let state: GameState = "....\n...x\n....\n....".parse().unwrap();
+        let out = state.render().last_move(Some((4, 2).into())).to_string();
+        assert_eq!(out.lines().nth(2), Some("3 . . .(X)3"));
+    }
+
+    #[test]
+    fn it_renders_other_orientations() {
+        let out = state().render().orientation(Orientation::Rotated).to_string();
+        assert_eq!(out.lines().next(), Some("  G F E D C B A"));
+        assert_eq!(out.lines().nth(1), Some("1 . . . . . . . 1"));
+        assert_eq!(out.lines().nth(3), Some("3 . . + . O . . 3"));
+
+        let out = state().render().orientation(Orientation::Flipped).to_string();
+        assert_eq!(out.lines().next(), Some("  A B C D E F G"));
+        assert_eq!(out.lines().nth(3), Some("3 . . O . + . . 3"));
+    }
+
+    #[test]
+    fn it_labels_wide_boards() {
+        let out = GameState::new(27, 2).render().to_string();
+        let mut lines = out.lines();
+        assert_eq!(lines.next(), Some(&format!(" {}A A", " ".repeat(51))[..]));
+        assert_eq!(lines.next(), Some("  A B C D E F G H J K L M N O P Q R S T U V W X Y Z A B"));
+
+        let out = GameState::new(9, 11).render().to_string();
+        assert_eq!(out.lines().nth(1), Some("11 . . . . . . . . . 11"));
+        assert_eq!(out.lines().nth(11), Some(" 1 . . . . . . . . . 1"));
+    }
+
+    #[test]
+    fn it_renders_a_game() {
+        let mut game = GameTree::new(9, 9);
+        game.play_move((3, 7), Color::Black).unwrap();
+        let node = game.play_move((7, 3), Color::White).unwrap();
+        let state = game.current_state().unwrap();
+        let out = state.render().last_move(game.last_move(node)).to_string();
+        assert_eq!(out.lines().nth(3), Some("7 . . + . . .(O). . 7"));
+        assert_eq!(out.lines().nth(7), Some("3 . . X . . . + . . 3"));
+    }
+
+    #[test]
+    fn it_gets_star_points() {
+        assert_eq!(star_points(19, 19).len(), 9);
+        assert_eq!(star_points(13, 13).len(), 5);
+        assert_eq!(star_points(9, 9), vec![(3, 3).into(), (7, 3).into(), (5, 5).into(), (3, 7).into(), (7, 7).into()]);
+        assert_eq!(star_points(10, 10).len(), 4);
+        assert!(star_points(5, 5).is_empty());
+    }
+}
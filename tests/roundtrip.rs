@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod roundtrip_tests {
+    use baduk_rs::{GameTree, SgfToken};
+    use std::convert::TryFrom;
+    use std::fs;
+
+    /// Every file in the corpus is in canonical form, so writing it back must give the same bytes
+    #[test]
+    fn it_round_trips_the_corpus() {
+        let mut count = 0;
+        for entry in fs::read_dir("tests/fixtures/roundtrip").unwrap() {
+            let path = entry.unwrap().path();
+            let input = fs::read_to_string(&path).unwrap();
+            let input = input.trim_end();
+            let game = GameTree::try_from(input).unwrap();
+            assert_eq!(game.to_sgf(), input, "{:?} was not written back unchanged", path);
+            count += 1;
+        }
+        assert!(count >= 6);
+    }
+
+    #[test]
+    fn it_keeps_private_properties_in_order() {
+        let input = fs::read_to_string("tests/fixtures/roundtrip/private_properties.sgf").unwrap();
+        let game = GameTree::try_from(input.as_str()).unwrap();
+
+        let node = game.get_node(1).unwrap();
+        assert_eq!(node.get_unknown_values("XX"), vec!["winrate=0.52 \\] lead=0.3", "second"]);
+        assert_eq!(node.tokens.len(), 3);
+
+        let node = game.get_node(2).unwrap();
+        assert_eq!(node.get_unknown_tokens(), vec![
+            &SgfToken::Unknown(("XY".to_string(), "".to_string())),
+            &SgfToken::Unknown(("ZZ".to_string(), "a\\\\b".to_string())),
+        ]);
+        assert_eq!(game.get_unknown_nodes(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn it_keeps_tokens_it_can_not_apply() {
+        let game = GameTree::try_from("(;SZ[9];B[aa];W[aa]XX[after])").unwrap();
+        assert_eq!(game.to_sgf(), "(;CA[UTF-8]SZ[9];B[aa];W[aa]XX[after])");
+    }
+
+    #[test]
+    fn it_keeps_unknown_tokens_from_sgf_parser_trees() {
+        let tree = sgf_parser::parse("(;SZ[19]XX[foo];B[aa]YY[bar])").unwrap();
+        let game: GameTree = (&tree).into();
+        assert_eq!(game.to_sgf(), "(;CA[UTF-8]SZ[19]XX[foo];B[aa]YY[bar])");
+    }
+}
@@ -0,0 +1,81 @@
+#[cfg(all(test, feature = "serde"))]
+mod serialize_tests {
+    use baduk_rs::{sgf, GameState, GameTree, Color, Position};
+    use serde_json::json;
+
+    #[test]
+    fn it_serializes_positions_and_states() {
+        assert_eq!(serde_json::to_value(Position::from((3, 4))).unwrap(), json!([3, 4]));
+
+        let mut state = GameState::new(3, 2).add_stone((1, 1), Color::Black).unwrap().add_stone((3, 2), Color::White).unwrap();
+        state.to_move = Some(Color::White);
+        state.komi = Some(6.5);
+        state.captures.black = 2;
+        let value = serde_json::to_value(&state).unwrap();
+        assert_eq!(value, json!({
+            "width": 3,
+            "height": 2,
+            "board": ["x..", "..o"],
+            "captures": {"white": 0, "black": 2},
+            "to_move": "W",
+            "ko": null,
+            "komi": 6.5
+        }));
+        let parsed: GameState = serde_json::from_value(value).unwrap();
+        assert!(parsed == state);
+    }
+
+    #[test]
+    fn it_rejects_invalid_states() {
+        let invalid = vec![
+            json!({"width": 2, "height": 1, "board": ["x"], "captures": {"white": 0, "black": 0}, "to_move": null, "ko": null, "komi": null}),
+            json!({"width": 2, "height": 1, "board": ["x?"], "captures": {"white": 0, "black": 0}, "to_move": null, "ko": null, "komi": null}),
+            json!({"width": 2, "height": 1, "board": ["x."], "captures": {"white": 0, "black": 0}, "to_move": "X", "ko": null, "komi": null}),
+            json!({"width": 2, "height": 1, "board": ["x."], "captures": {"white": 0, "black": 0}, "to_move": null, "ko": [0, 3], "komi": null}),
+            json!({"width": 2, "height": 1, "board": ["x."], "captures": {"white": 0, "black": 0}, "to_move": null, "ko": [1, 1], "komi": null}),
+        ];
+        for value in invalid {
+            assert!(serde_json::from_value::<GameState>(value).is_err());
+        }
+    }
+
+    #[test]
+    fn it_serializes_tokens_as_sgf_properties() {
+        let game = sgf::parse("(;SZ[9]C[a \\] b]XX[custom];B[cc])").unwrap();
+        let value = serde_json::to_value(&game).unwrap();
+        assert_eq!(value["nodes"][0]["tokens"], json!([
+            {"property": "SZ", "value": "9"},
+            {"property": "C", "value": "a \\] b"},
+            {"property": "XX", "value": "custom"}
+        ]));
+        assert_eq!(value["nodes"][1]["tokens"], json!([{"property": "B", "value": "cc"}]));
+        assert_eq!(value["nodes"][1]["parent"], json!(0));
+        assert_eq!(value["current"], json!(1));
+    }
+
+    #[test]
+    fn it_round_trips_game_trees() {
+        let input = "(;SZ[9]KM[6.5];B[cc];W[dd](;B[ee])(;B[ff]C[variation]))";
+        let game = sgf::parse_with_checkpoints(input, 2).unwrap();
+        let json = serde_json::to_string(&game).unwrap();
+        let parsed: GameTree = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.to_sgf(), game.to_sgf());
+        assert_eq!(parsed.current, game.current);
+        assert_eq!(parsed.checkpoint_interval(), Some(2));
+        assert!(parsed.current_state().is_some());
+        let last = parsed.nodes.len() - 1;
+        assert_eq!(parsed.state(last).unwrap().get_stone((6, 6)), Some(&Color::Black));
+    }
+
+    #[test]
+    fn it_rejects_inconsistent_trees() {
+        let game = sgf::parse("(;SZ[9];B[cc];W[dd])").unwrap();
+        let mut value = serde_json::to_value(&game).unwrap();
+        value["nodes"][2]["parent"] = json!(0);
+        assert!(serde_json::from_value::<GameTree>(value).is_err());
+
+        let mut value = serde_json::to_value(&game).unwrap();
+        value["current"] = json!(5);
+        assert!(serde_json::from_value::<GameTree>(value).is_err());
+    }
+}
@@ -0,0 +1,200 @@
+#[cfg(test)]
+mod sgf_tests {
+    use baduk_rs::{GameTree, Color, SgfToken};
+    use baduk_rs::sgf::{self, PropertyType};
+    use sgf_parser::Action;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn it_parses_moves_and_variations() {
+        let game = sgf::parse("(;SZ[9];B[cc];W[gg](;B[cg])(;B[gc];W[cd]))").unwrap();
+        assert_eq!(game.count_nodes(), 6);
+        assert_eq!(game.nodes[2].children.len(), 2);
+        let state = game.get_node(5).unwrap().state.as_ref().unwrap();
+        assert_eq!(state.dimensions(), (9, 9));
+        assert_eq!(state.get_stone((7, 3)), Some(&Color::Black));
+        assert_eq!(state.get_stone((3, 4)), Some(&Color::White));
+        assert_eq!(state.get_stone((3, 7)), None);
+    }
+
+    #[test]
+    fn it_defaults_to_19x19() {
+        let game = sgf::parse("(;GM[1];B[pd])").unwrap();
+        assert_eq!(game.current_state().unwrap().dimensions(), (19, 19));
+    }
+
+    #[test]
+    fn it_places_root_setup_on_the_default_board() {
+        let game = sgf::parse("(;AB[dd][pp];W[qd])").unwrap();
+        let state = game.current_state().unwrap();
+        assert_eq!(state.get_stone((4, 4)), Some(&Color::Black));
+        assert_eq!(state.get_stone((16, 16)), Some(&Color::Black));
+        assert_eq!(state.get_stone((17, 4)), Some(&Color::White));
+
+        let game = sgf::parse("(;AB[cc]SZ[9])").unwrap();
+        let state = game.current_state().unwrap();
+        assert_eq!(state.dimensions(), (9, 9));
+        assert_eq!(state.get_stone((3, 3)), Some(&Color::Black));
+    }
+
+    #[test]
+    fn it_handles_passes() {
+        let game = sgf::parse("(;SZ[19];B[];W[tt])").unwrap();
+        assert_eq!(game.nodes[1].tokens[0], SgfToken::Move { color: Color::Black, action: Action::Pass });
+        assert_eq!(game.nodes[2].tokens[0], SgfToken::Move { color: Color::White, action: Action::Move(20, 20) });
+        assert!(game.current_state().unwrap().is_empty());
+    }
+
+    #[test]
+    fn it_unescapes_text() {
+        let game = sgf::parse("(;SZ[19]C[a \\] b \\\\ c\\\nd]PB[Black\\:1])").unwrap();
+        let root = game.get_node(0).unwrap();
+        assert_eq!(root.tokens[1], SgfToken::Comment("a ] b \\ cd".to_string()));
+        assert_eq!(root.tokens[2], SgfToken::PlayerName { color: Color::Black, name: "Black:1".to_string() });
+    }
+
+    #[test]
+    fn it_reads_compressed_point_lists() {
+        let game = sgf::parse("(;SZ[19]AB[aa:bc]AW[dd];AE[aa:ab])").unwrap();
+        let state = game.get_node(0).unwrap().state.as_ref().unwrap();
+        assert_eq!(state.get_stone((1, 1)), Some(&Color::Black));
+        assert_eq!(state.get_stone((2, 3)), Some(&Color::Black));
+        assert_eq!(state.get_stone((4, 4)), Some(&Color::White));
+        assert_eq!(game.get_node(0).unwrap().tokens.len(), 3);
+        assert_eq!(game.get_setup(0).len(), 7);
+        let state = game.current_state().unwrap();
+        assert_eq!(state.get_stone((1, 1)), None);
+        assert_eq!(state.get_stone((1, 2)), None);
+        assert_eq!(state.get_stone((2, 2)), Some(&Color::Black));
+        assert_eq!(sgf::write(&game), "(;CA[UTF-8]SZ[19]AB[aa:bc]AW[dd];AE[aa:ab])");
+    }
+
+    #[test]
+    fn it_splits_compressed_point_lists_when_editing() {
+        let mut game = sgf::parse("(;SZ[19]AB[aa:ba])").unwrap();
+        game.erase_stone_on_node((1, 1), 0).unwrap();
+        assert_eq!(sgf::write(&game), "(;CA[UTF-8]SZ[19]AB[ba]AE[aa])");
+        assert_eq!(game.current_state().unwrap().get_stone((2, 1)), Some(&Color::Black));
+    }
+
+    #[test]
+    fn it_keeps_properties_not_modelled_by_tokens() {
+        let game = sgf::parse("(;SZ[19]AE[aa]CR[bb][cc]PL[W]N[Start])").unwrap();
+        let tokens = &game.get_node(0).unwrap().tokens;
+        assert_eq!(tokens[1], SgfToken::Unknown(("AE".to_string(), "aa".to_string())));
+        assert_eq!(tokens[2], SgfToken::Unknown(("CR".to_string(), "bb".to_string())));
+        assert_eq!(tokens[3], SgfToken::Unknown(("CR".to_string(), "cc".to_string())));
+        assert_eq!(tokens[4], SgfToken::Unknown(("PL".to_string(), "W".to_string())));
+        assert_eq!(tokens[5], SgfToken::Unknown(("N".to_string(), "Start".to_string())));
+    }
+
+    #[test]
+    fn it_marks_invalid_values() {
+        let game = sgf::parse("(;SZ[19]KM[lots]PL[X]GB[3])").unwrap();
+        let tokens = &game.get_node(0).unwrap().tokens;
+        assert_eq!(tokens[1], SgfToken::Invalid(("KM".to_string(), "lots".to_string())));
+        assert_eq!(tokens[2], SgfToken::Invalid(("PL".to_string(), "X".to_string())));
+        assert_eq!(tokens[3], SgfToken::Invalid(("GB".to_string(), "3".to_string())));
+    }
+
+    #[test]
+    fn it_accepts_old_style_identifiers_and_whitespace() {
+        let game = sgf::parse("junk before ( ;SiZe[19] CoPyright [me]\n ; B [aa] )").unwrap();
+        assert_eq!(game.count_nodes(), 2);
+        assert_eq!(game.get_node(0).unwrap().tokens[1], SgfToken::Copyright("me".to_string()));
+    }
+
+    #[test]
+    fn it_keeps_lower_case_identifiers() {
+        let game = sgf::parse("(;SZ[19]foo[bar])").unwrap();
+        let tokens = &game.get_node(0).unwrap().tokens;
+        assert_eq!(tokens[1], SgfToken::Unknown(("foo".to_string(), "bar".to_string())));
+        assert_eq!(sgf::write(&game), "(;CA[UTF-8]SZ[19]foo[bar])");
+    }
+
+    #[test]
+    fn it_returns_errors_for_broken_input() {
+        assert!(sgf::parse("").is_err());
+        assert!(sgf::parse("(;SZ[19]").is_err());
+        assert!(sgf::parse("(;SZ[19)").is_err());
+        assert!(sgf::parse("(SZ[19])").is_err());
+        assert!(GameTree::try_from("(;B)").is_err());
+    }
+
+    #[test]
+    fn it_writes_sgf() {
+        let mut game = GameTree::new(19, 19);
+        game.add_token(0, &SgfToken::Comment("a]b".to_string()));
+        let _ = game.play_move((4, 4), Color::Black);
+        let _ = game.play_move_as_variation((16, 16), Color::Black, 0);
+        assert_eq!(game.to_sgf(), "(;CA[UTF-8]SZ[19]C[a\\]b](;B[dd])(;B[pp]))");
+    }
+
+    #[test]
+    fn it_round_trips_sgf() {
+        let input = "(;CA[UTF-8]FF[4]GM[1]SZ[19:13]AP[baduk\\:rs:1.0]PB[Black]KM[6.5]RE[W+R]AB[aa][bb]LB[cc:A\\:1]TR[dd]XX[raw \\] value];B[ee]BL[30]OB[5](;W[ff]C[line\none])(;W[]GB[1]))";
+        let game = GameTree::try_from(input).unwrap();
+        assert_eq!(game.to_sgf(), input);
+    }
+
+    #[test]
+    fn it_knows_property_types() {
+        assert_eq!(sgf::property_type("AE"), Some(PropertyType::Setup));
+        assert_eq!(sgf::property_type("MA"), Some(PropertyType::Markup));
+        assert_eq!(sgf::property_type("TE"), Some(PropertyType::MoveAnnotation));
+        assert_eq!(sgf::property_type("SZ"), Some(PropertyType::Root));
+        assert_eq!(sgf::property_type("XX"), None);
+    }
+
+    #[test]
+    fn it_parses_collections() {
+        let games = sgf::parse_collection("(;SZ[19];B[aa])\n(;SZ[9];B[bb];W[cc])(;SZ[13])").unwrap();
+        assert_eq!(games.len(), 3);
+        assert_eq!(games[0].count_nodes(), 2);
+        assert_eq!(games[1].count_nodes(), 3);
+        assert_eq!(games[2].current_state().unwrap().dimensions(), (13, 13));
+
+        assert!(sgf::parse_collection("(;SZ[19])(;SZ[19]").is_err());
+        assert!(sgf::parse_collection("(;SZ[19]) junk").is_err());
+    }
+
+    #[test]
+    fn it_writes_collections() {
+        let input = "(;CA[UTF-8]SZ[19];B[aa])\n(;CA[UTF-8]SZ[9];B[bb](;W[cc])(;W[dd]))";
+        let games = sgf::parse_collection(input).unwrap();
+        assert_eq!(sgf::write_collection(&games), input);
+    }
+
+    #[test]
+    fn it_streams_collections() {
+        let input = "header text (;SZ[19]C[(not a tree\\])];B[aa])\n\n(;SZ[9];B[bb])\n";
+        let reader = sgf::CollectionReader::new(std::io::BufReader::with_capacity(4, input.as_bytes()));
+        let games = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].get_node(0).unwrap().tokens[1], SgfToken::Comment("(not a tree])".to_string()));
+        assert_eq!(games[1].current_state().unwrap().dimensions(), (9, 9));
+
+        let mut reader = sgf::CollectionReader::new("(;SZ[19])(;SZ[19]".as_bytes());
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().unwrap().is_err());
+    }
+
+    #[test]
+    fn it_parses_with_checkpoints() {
+        let input = "(;SZ[9];B[aa];W[ba];B[ca];W[ab];B[cc](;W[dd];B[ee])(;W[bb]))";
+        let full = sgf::parse(input).unwrap();
+        let game = sgf::parse_with_checkpoints(input, 3).unwrap();
+        assert_eq!(game.checkpoint_interval(), Some(3));
+        let stored: Vec<usize> = (0..game.count_nodes()).filter(|node| game.nodes[*node].state.is_some()).collect();
+        assert_eq!(stored, vec![0, 3, 6, 8]);
+        for node in 0..game.count_nodes() {
+            assert_eq!(game.state(node).as_deref(), full.nodes[node].state.as_ref());
+        }
+        assert_eq!(game.to_sgf(), full.to_sgf());
+
+        let reader = sgf::CollectionReader::new(input.as_bytes()).checkpoint_interval(3);
+        let games = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(games[0].checkpoint_interval(), Some(3));
+        assert!(games[0].nodes[4].state.is_none());
+    }
+}
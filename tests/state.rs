@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod state_tests {
-    use baduk_rs::{BadukError, BadukErrorKind, GameState, Color};
+    use baduk_rs::{BadukErrorKind, GameState, Color};
 
     #[test]
     fn it_creates_empty_state() {
@@ -26,6 +26,103 @@ mod state_tests {
         }
     }
 
+    #[test]
+    fn it_can_parse_rectangular_boards() {
+        let state: GameState = "
+        .x..o
+        ...o.
+        x...."
+            .parse()
+            .unwrap();
+        assert_eq!(state.dimensions(), (5, 3));
+        assert_eq!(state.get_stone((2, 1)), Some(&Color::Black));
+        assert_eq!(state.get_stone((4, 2)), Some(&Color::White));
+        assert_eq!(state.get_stone((1, 3)), Some(&Color::Black));
+        assert_eq!(format!("{:?}", state), ".x..o\n...o.\nx....");
+
+        let err = "..x\n.o".parse::<GameState>().unwrap_err();
+        assert_eq!(err.kind, BadukErrorKind::InvalidInputSize);
+    }
+
+    #[test]
+    fn it_lists_the_board_by_rows() {
+        let state: GameState = "x..\n..o".parse().unwrap();
+        assert_eq!(state.board(), vec![Some(Color::Black), None, None, None, None, Some(Color::White)]);
+    }
+
+    #[test]
+    fn it_supports_boards_up_to_52() {
+        let row = ".".repeat(52);
+        let state: GameState = [row.as_str(); 2].join("\n").parse().unwrap();
+        assert_eq!(state.dimensions(), (52, 2));
+
+        let row = ".".repeat(53);
+        let err = [row.as_str(); 2].join("\n").parse::<GameState>().unwrap_err();
+        assert_eq!(err.kind, BadukErrorKind::InvalidInputSize);
+        let err = ["."; 53].join("\n").parse::<GameState>().unwrap_err();
+        assert_eq!(err.kind, BadukErrorKind::InvalidInputSize);
+    }
+
+    #[test]
+    fn it_can_print_coordinate_labels() {
+        let state = GameState::new(3, 2).place_stone((3, 2), Color::Black).unwrap();
+        assert_eq!(format!("{:#?}", state), "  abc\na ...\nb ..x");
+
+        let state = GameState::new(28, 1);
+        let out = format!("{:#?}", state);
+        assert!(out.starts_with("  abcdefghijklmnopqrstuvwxyzAB\na "));
+    }
+
+    #[test]
+    fn it_can_write_and_read_the_full_state() {
+        let mut state: GameState = "
+        .x..o
+        x.o..
+        ....."
+            .parse()
+            .unwrap();
+        state.to_move = Some(Color::White);
+        state.captures.white = 2;
+        state.ko = Some((1, 1).into());
+        state.komi = Some(6.5);
+        let text = state.to_string();
+        assert_eq!(
+            text,
+            "size: 5x3\nto-move: white\ncaptures: black 0, white 2\nko: aa\nkomi: 6.5\n.x..o\nx.o..\n....."
+        );
+        assert!(text.parse::<GameState>().unwrap() == state);
+
+        let state = GameState::new(3, 3);
+        assert_eq!(state.to_string(), "size: 3x3\ncaptures: black 0, white 0\n...\n...\n...");
+        assert!(state.to_string().parse::<GameState>().unwrap() == state);
+    }
+
+    #[test]
+    fn it_can_read_headers() {
+        let state: GameState = "size: 4x2\nto-move: B\ncaptures: white 3\n.x..o...".parse().unwrap();
+        assert_eq!(state.dimensions(), (4, 2));
+        assert_eq!(state.get_stone((1, 2)), Some(&Color::White));
+        assert_eq!(state.to_move, Some(Color::Black));
+        assert_eq!(state.captures().white, 3);
+        assert_eq!(state.captures().black, 0);
+        assert_eq!(state.ko, None);
+        assert_eq!(state.komi, None);
+    }
+
+    #[test]
+    fn it_rejects_invalid_headers() {
+        let parse = |text: &str| text.parse::<GameState>().unwrap_err().kind;
+        assert_eq!(parse("size: 3x2\n...\n...\n..."), BadukErrorKind::InvalidInputSize);
+        assert_eq!(parse("size: 3\n..."), BadukErrorKind::InvalidInputSize);
+        assert_eq!(parse("size: 70000x70000\n."), BadukErrorKind::InvalidInputSize);
+        assert_eq!(parse("to-move: red\n...."), BadukErrorKind::InvalidInput);
+        assert_eq!(parse("captures: black\n...."), BadukErrorKind::InvalidInput);
+        assert_eq!(parse("ko: 11\n...."), BadukErrorKind::InvalidInput);
+        assert_eq!(parse("ko: cc\n...."), BadukErrorKind::InvalidPosition((3, 3).into()));
+        assert_eq!(parse("komi: lots\n...."), BadukErrorKind::InvalidInput);
+        assert_eq!(parse("rules: japanese\n...."), BadukErrorKind::InvalidInput);
+    }
+
     #[test]
     fn it_returns_error_on_invalid_position() {
         let state = GameState::default();
@@ -53,6 +150,22 @@ mod state_tests {
         assert_eq!(format!("{:?}", state), "o..\n.x.\n...");
     }
 
+    #[test]
+    fn it_can_add_a_stone() {
+        let state = GameState::new(5, 5);
+        let state = state.add_stone((2, 2), Color::Black).unwrap();
+        let state = state.add_stone((2, 2), Color::White).unwrap();
+        assert_eq!(state.get_stone((2, 2)), Some(&Color::White));
+        assert_eq!(
+            state.add_stone((6, 1), Color::Black).unwrap_err().kind,
+            BadukErrorKind::InvalidPosition((6, 1).into())
+        );
+        assert_eq!(
+            state.add_stone((0, 3), Color::Black).unwrap_err().kind,
+            BadukErrorKind::InvalidPosition((0, 3).into())
+        );
+    }
+
     #[test]
     fn it_cannot_place_at_same_location() {
         let state = GameState::default();
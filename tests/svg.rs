@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod svg_tests {
+    use baduk_rs::{GameState, GameTree, Color, Markup};
+
+    fn count(svg: &str, pattern: &str) -> usize {
+        svg.matches(pattern).count()
+    }
+
+    #[test]
+    fn it_renders_a_board() {
+        let state: GameState = ".........\n.........\n..x......\n.........\n....o....\n.........\n.........\n.........\n.........".parse().unwrap();
+        let svg = state.svg().to_string();
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="264" height="264" viewBox="0 0 264 264">"#));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains(r##"<path d="M36 36V228M60 36V228"##));
+        // Star points are drawn below the stones
+        assert_eq!(count(&svg, r##"r="2.4" fill="#000"/>"##), 5);
+        assert!(svg.contains(r##"<circle cx="84" cy="84" r="11.52" fill="#000" stroke="#000" stroke-width="1"/>"##));
+        assert!(svg.contains(r##"<circle cx="132" cy="132" r="11.52" fill="#fff" stroke="#000" stroke-width="1"/>"##));
+        // Every column and row is labeled on both sides
+        assert_eq!(count(&svg, "<text "), 36);
+        assert!(svg.contains(r##"<text x="36" y="12" font-family="sans-serif" font-size="10.8" fill="#000" text-anchor="middle" dominant-baseline="central">A</text>"##));
+        assert!(svg.contains(">J</text>"));
+        assert!(!svg.contains(">I</text>"));
+    }
+
+    #[test]
+    fn it_renders_without_coordinates() {
+        let state = GameState::new(5, 3);
+        let svg = state.svg().coordinates(false).cell_size(10).to_string();
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="50" height="30" viewBox="0 0 50 30">"#));
+        assert_eq!(count(&svg, "<text "), 0);
+    }
+
+    #[test]
+    fn it_renders_move_numbers() {
+        let mut game = GameTree::new(9, 9);
+        game.play_move((3, 3), Color::Black).unwrap();
+        game.play_move((7, 7), Color::White).unwrap();
+        let node = game.play_move((3, 7), Color::Black).unwrap();
+        assert_eq!(game.move_numbers(node, 2..=3), vec![((7, 7).into(), 2), ((3, 7).into(), 3)]);
+
+        let svg = game.svg(node).unwrap().coordinates(false).move_numbers(game.move_numbers(node, 2..=3)).to_string();
+        assert!(svg.contains(r##"fill="#000" text-anchor="middle" dominant-baseline="central">2</text>"##));
+        assert!(svg.contains(r##"fill="#fff" text-anchor="middle" dominant-baseline="central">3</text>"##));
+        assert!(!svg.contains(">1</text>"));
+    }
+
+    #[test]
+    fn it_renders_markup() {
+        let mut game = GameTree::new(9, 9);
+        let node = game.play_move((3, 3), Color::Black).unwrap();
+        game.add_markup(node, Markup::Circle((3, 3).into())).unwrap();
+        game.add_markup(node, Markup::Triangle((4, 4).into())).unwrap();
+        game.add_markup(node, Markup::Square((5, 5).into())).unwrap();
+        game.add_markup(node, Markup::Cross((6, 6).into())).unwrap();
+        game.add_markup(node, Markup::Label((7, 7).into(), "<A>".to_string())).unwrap();
+        game.add_markup(node, Markup::Arrow((1, 1).into(), (2, 2).into())).unwrap();
+        game.add_markup(node, Markup::Line((1, 2).into(), (2, 3).into())).unwrap();
+        game.add_markup(node, Markup::Dimmed((9, 9).into())).unwrap();
+
+        let svg = game.svg(node).unwrap().to_string();
+        assert!(svg.contains(r##"<circle cx="84" cy="84" r="6" fill="none" stroke="#fff" stroke-width="2"/>"##));
+        assert_eq!(count(&svg, "<polygon "), 1);
+        assert_eq!(count(&svg, r#"<rect x="#), 2);
+        assert!(svg.contains(">&lt;A&gt;</text>"));
+        assert_eq!(count(&svg, r#"<marker id="arrowhead""#), 1);
+        assert_eq!(count(&svg, r#"marker-end="url(#arrowhead)""#), 1);
+        assert_eq!(count(&svg, "<line "), 2);
+        assert!(svg.contains(r#"fill-opacity="0.5""#));
+    }
+
+    #[test]
+    fn it_requires_a_board() {
+        let game = GameTree::default();
+        assert!(game.svg(0).is_err());
+    }
+}
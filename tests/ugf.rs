@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod ugf_tests {
+    use baduk_rs::{ugf, sgf, BadukErrorKind, GameResult, Color};
+    use std::fs;
+
+    #[test]
+    fn it_reads_ugf_files() {
+        let input = fs::read_to_string("tests/fixtures/formats/sample.ugf").unwrap();
+        let game = ugf::parse(&input).unwrap();
+        assert_eq!(game.player_name(Color::Black).as_deref(), Some("Iyama Yuta"));
+        assert_eq!(game.result(), Some(GameResult::WinByScore(Color::Black, 2.5)));
+        assert_eq!(
+            game.to_sgf(),
+            "(;CA[UTF-8]SZ[19]GN[Kisei]PC[Tokyo]DT[2011-01-13]PB[Iyama Yuta]BR[9p]PW[Cho U]WR[9p]KM[6.5]RE[B+2.5];B[pp];W[dd];B[qd])"
+        );
+    }
+
+    #[test]
+    fn it_writes_what_it_reads() {
+        let input = "(;SZ[9]PB[Black]BR[1d]PW[White]KM[0.5]HA[2]RE[W+R]AB[gc][cg];W[ee];B[];W[cc])";
+        let game = sgf::parse(input).unwrap();
+        let written = ugf::write(&game).unwrap();
+        assert!(written.contains("Hdcp=2,0.5\n"));
+        assert!(written.contains("Winner=W,C\n"));
+        assert!(written.contains("\nGG,B0,0\n"));
+        assert!(written.contains("\nEE,W1,0\n"));
+        assert_eq!(ugf::parse(&written).unwrap().to_sgf(), game.to_sgf());
+    }
+
+    #[test]
+    fn it_only_writes_square_boards_up_to_25() {
+        for input in &["(;SZ[9:13];B[cl])", "(;SZ[26];B[cc])"] {
+            let err = ugf::write(&sgf::parse(input).unwrap()).unwrap_err();
+            assert_eq!(err.kind, BadukErrorKind::InvalidInputSize);
+        }
+    }
+
+    #[test]
+    fn it_writes_passes_off_the_board() {
+        let game = sgf::parse("(;SZ[25]KM[0];B[ya];W[];B[ay])").unwrap();
+        let written = ugf::write(&game).unwrap();
+        assert!(written.contains("\nYY,B1,0\n"));
+        assert_eq!(ugf::parse(&written).unwrap().to_sgf(), game.to_sgf());
+    }
+
+    #[test]
+    fn it_rejects_invalid_colors() {
+        for data in &["QD,黒1,0", "QD,X1,0", "QD,,0", "QD,B,0"] {
+            let input = format!("[Header]\nSize=19\n[Data]\n{}\n", data);
+            assert_eq!(ugf::parse(&input).unwrap_err().kind, BadukErrorKind::InvalidInput);
+        }
+    }
+
+    #[test]
+    fn it_decodes_shift_jis() {
+        let input = "[Header]\nPlayerB=井山裕太,9p\nSize=19\n[Data]\nPD,B1,0\n";
+        let (bytes, _, _) = encoding_rs::SHIFT_JIS.encode(input);
+        let game = ugf::parse_bytes(&bytes).unwrap();
+        assert_eq!(game.player_name(Color::Black).as_deref(), Some("井山裕太"));
+        assert_eq!(game.count_nodes(), 2);
+    }
+}